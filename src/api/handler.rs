@@ -1,8 +1,25 @@
 use crate::api::state::AppState;
+use crate::error::{Error, retry_after_secs};
+use crate::middleware::auth::AuthenticatedKey;
+use crate::model::award::{LEGACY_PERIOD, TopRecipeAward};
+use crate::model::batch_request::{BatchItemResult, BatchItemStatus, BatchUpdateRequest};
 use crate::model::level::LevelRequest;
-use axum::{Json, extract::State, response::IntoResponse};
+use crate::model::review::{ReviewAction, ReviewDecisionRequest, ReviewStatus};
+use crate::model::verification_request::VerificationOverrideRequest;
+use crate::service::health::{PROCESSOR_STALE_AFTER_SECS, check_readiness};
+use crate::service::metrics::PrometheusText;
+use crate::utils::badge::top_recipe_tier_badges;
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use mongodb::bson::oid::ObjectId;
 use serde_json::json;
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub async fn health_handler() -> impl IntoResponse {
     Json(serde_json::json!({
@@ -10,6 +27,110 @@ pub async fn health_handler() -> impl IntoResponse {
     }))
 }
 
+/// Readiness probe: 503 with a per-component breakdown when a dependency is failing.
+pub async fn readiness_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let report = check_readiness(
+        &state.connectivity,
+        &state.processor_heartbeat,
+        state.badge_queue.as_ref(),
+        state.notifier.as_ref(),
+        chrono::Utc::now(),
+    )
+    .await;
+
+    // Fail readiness while shutting down so the load balancer stops routing here
+    let shutting_down = state.shutdown.is_triggered();
+    let (status, label) = match (report.ready, shutting_down) {
+        (_, true) => (StatusCode::SERVICE_UNAVAILABLE, "shutting_down"),
+        (true, false) => (StatusCode::OK, "ok"),
+        (false, false) => (StatusCode::SERVICE_UNAVAILABLE, "degraded"),
+    };
+    (
+        status,
+        Json(json!({
+            "status": label,
+            "components": report.components
+        })),
+    )
+}
+
+/// Prometheus metrics, served on the metrics port.
+pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let processor = state.processor_metrics.snapshot();
+    let processor_up = state.processor_heartbeat.is_alive(
+        chrono::Duration::seconds(PROCESSOR_STALE_AFTER_SECS),
+        chrono::Utc::now(),
+    );
+    let pending = state.badge_queue.get_pending_requests().await.len();
+
+    let mut metrics = PrometheusText::new();
+    metrics
+        .gauge(
+            "badge_forge_processor_up",
+            "Whether the processor task reported a heartbeat recently",
+            processor_up as u64,
+        )
+        .counter(
+            "badge_forge_processor_restarts_total",
+            "Times the processor task died and was restarted",
+            processor.restarts,
+        )
+        .counter(
+            "badge_forge_processor_panics_total",
+            "Badge updates whose processing panicked",
+            processor.panics,
+        )
+        .counter(
+            "badge_forge_requests_processed_total",
+            "Badge updates processed successfully",
+            processor.processed,
+        )
+        .counter(
+            "badge_forge_requests_failed_total",
+            "Badge updates dropped after an error",
+            processor.failed,
+        )
+        .gauge(
+            "badge_forge_queue_pending",
+            "Badge updates waiting in the queue",
+            pending as u64,
+        )
+        .gauge(
+            "badge_forge_database_up",
+            "Whether the last database ping succeeded",
+            state.connectivity.is_ready() as u64,
+        );
+    if let Some(capacity) = state.badge_queue.capacity() {
+        metrics.gauge(
+            "badge_forge_queue_capacity",
+            "Maximum number of queued badge updates",
+            capacity as u64,
+        );
+    }
+    if let Some(cache) = &state.cache {
+        let cache = cache.snapshot();
+        metrics
+            .counter(
+                "badge_forge_cache_hits_total",
+                "Database reads served from the cache",
+                cache.hits,
+            )
+            .counter(
+                "badge_forge_cache_misses_total",
+                "Database reads not served from the cache",
+                cache.misses,
+            );
+    }
+
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4",
+        )],
+        metrics.finish(),
+    )
+}
+
 pub async fn version_handler() -> impl IntoResponse {
     Json(serde_json::json!({
         "version": env!("CARGO_PKG_VERSION")
@@ -20,28 +141,182 @@ pub async fn update_badges_handler(
     State(state): State<Arc<AppState>>,
     Json(mut request): Json<LevelRequest>,
 ) -> impl IntoResponse {
-    if request.request_id.is_empty() {
-        request.request_id = uuid::Uuid::new_v4().to_string();
+    if state.shutdown.is_triggered() {
+        return Error::Transient("Service is shutting down".to_string()).into_response();
     }
 
-    if request.created_at.timestamp() == 0 {
-        request.created_at = chrono::Utc::now();
-    }
-    match state.badge_queue.enqueue(request.clone()).await {
+    // Both are set here rather than trusted from the client: request ids key the status
+    // reported by `/requests`, and the processor drops cached recipe data loaded before
+    // the creation time
+    request.request_id = uuid::Uuid::new_v4().to_string();
+    request.created_at = chrono::Utc::now();
+    match state.badge_queue.try_enqueue(request.clone()).await {
         Ok(_) => Json(json!({
             "status": "queued",
             "message": "Badge update request has been queued for processing",
-            "user_id": request.user_id
+            "user_id": request.user_id,
+            "request_id": request.request_id,
+            "created_at": request.created_at
         }))
         .into_response(),
-        Err(e) => (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "status": "error",
-                "message": format!("Failed to queue badge update request: {}", e)
-            })),
-        )
-            .into_response(),
+        // Already logged by the queue
+        Err(e @ Error::TooManyRequests { .. }) => e.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to queue badge update request: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+/// Invalid ids listed in the error of a rejected batch, to keep the message short.
+const MAX_REPORTED_INVALID_IDS: usize = 10;
+
+/// Enqueues badge updates for many users at once, e.g. after an import. Every id is validated
+/// before anything is queued; items that cannot be queued are reported as `rejected`.
+pub async fn batch_update_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<BatchUpdateRequest>,
+) -> impl IntoResponse {
+    if state.shutdown.is_triggered() {
+        return Error::Transient("Service is shutting down".to_string()).into_response();
+    }
+
+    let user_ids = request.user_ids;
+    if user_ids.is_empty() {
+        return Error::Validation("user_ids must not be empty".to_string()).into_response();
+    }
+    if user_ids.len() > state.batch_max_size {
+        return Error::Validation(format!(
+            "A batch holds at most {} user ids, got {}",
+            state.batch_max_size,
+            user_ids.len()
+        ))
+        .into_response();
+    }
+    let invalid: Vec<&str> = user_ids
+        .iter()
+        .filter(|user_id| ObjectId::parse_str(user_id).is_err())
+        .map(String::as_str)
+        .collect();
+    if !invalid.is_empty() {
+        let mut message = format!(
+            "Invalid user ID format: {}",
+            invalid[..invalid.len().min(MAX_REPORTED_INVALID_IDS)].join(", ")
+        );
+        if invalid.len() > MAX_REPORTED_INVALID_IDS {
+            message.push_str(&format!(
+                " and {} more",
+                invalid.len() - MAX_REPORTED_INVALID_IDS
+            ));
+        }
+        return Error::InvalidId(message).into_response();
+    }
+
+    let now = Instant::now();
+    let created_at = chrono::Utc::now();
+    let mut seen = HashSet::new();
+    let mut retry_after: Option<Duration> = None;
+    let mut results = Vec::with_capacity(user_ids.len());
+    for user_id in user_ids {
+        if !seen.insert(user_id.clone()) {
+            results.push(BatchItemResult {
+                user_id,
+                status: BatchItemStatus::Duplicate,
+                request_id: None,
+                message: None,
+            });
+            continue;
+        }
+
+        let outcome = match state.rate_limits.per_user.check(&user_id, now) {
+            Ok(()) => {
+                let request = LevelRequest {
+                    user_id: user_id.clone(),
+                    request_id: uuid::Uuid::new_v4().to_string(),
+                    created_at,
+                };
+                let request_id = request.request_id.clone();
+                state
+                    .badge_queue
+                    .try_enqueue(request)
+                    .await
+                    .map(|()| request_id)
+            }
+            Err(wait) => Err(Error::TooManyRequests {
+                message: format!("Too many badge updates requested for user {}", user_id),
+                retry_after: wait,
+            }),
+        };
+        results.push(match outcome {
+            Ok(request_id) => BatchItemResult {
+                user_id,
+                status: BatchItemStatus::Queued,
+                request_id: Some(request_id),
+                message: None,
+            },
+            Err(e) => {
+                match &e {
+                    Error::TooManyRequests {
+                        retry_after: wait, ..
+                    } => {
+                        retry_after = retry_after.max(Some(*wait));
+                    }
+                    _ => tracing::error!("Failed to queue badge update request: {}", e),
+                }
+                BatchItemResult {
+                    user_id,
+                    status: BatchItemStatus::Rejected,
+                    request_id: None,
+                    message: Some(e.public_message()),
+                }
+            }
+        });
+    }
+
+    let count = |status| {
+        results
+            .iter()
+            .filter(|result| result.status == status)
+            .count()
+    };
+    let (queued, rejected) = (
+        count(BatchItemStatus::Queued),
+        count(BatchItemStatus::Rejected),
+    );
+    tracing::info!(
+        "Batch of {} user ids: {} queued, {} rejected",
+        results.len(),
+        queued,
+        rejected
+    );
+
+    let mut response = Json(json!({
+        "status": "ok",
+        "queued": queued,
+        "rejected": rejected,
+        "results": results
+    }))
+    .into_response();
+    if let Some(retry_after) = retry_after {
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, retry_after_secs(retry_after).into());
+    }
+    response
+}
+
+/// What became of a badge update request, while it is still tracked.
+pub async fn request_status_handler(
+    State(state): State<Arc<AppState>>,
+    Path(request_id): Path<String>,
+) -> impl IntoResponse {
+    match state.requests.get(&request_id) {
+        Some(request) => Json(json!({
+            "status": "ok",
+            "request": request
+        }))
+        .into_response(),
+        None => Error::NotFound(format!("Request not found: {}", request_id)).into_response(),
     }
 }
 
@@ -52,7 +327,8 @@ pub async fn queue_status_handler(State(state): State<Arc<AppState>>) -> impl In
     Json(json!({
         "status": "ok",
         "pending_count": pending_count,
-        "pending_requests": pending_requests
+        "pending_requests": pending_requests,
+        "cache": state.cache.as_ref().map(|counters| counters.snapshot())
     }))
 }
 
@@ -62,31 +338,19 @@ pub async fn award_top_recipe_handler(
 ) -> impl IntoResponse {
     tracing::info!("Award top recipe request: {:?}", request);
 
-    let category = match crate::model::category::Category::parse(&request.category) {
+    let category = match state.categories.parse(&request.category) {
         Some(cat) => cat,
         None => {
-            return (
-                axum::http::StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "status": "error",
-                    "message": format!("Invalid category: {}", request.category)
-                })),
-            )
+            return Error::Validation(format!("Invalid category: {}", request.category))
                 .into_response();
         }
     };
     let badge_name = category.badge_name();
 
-    let user_id = match mongodb::bson::oid::ObjectId::parse_str(&request.user_id) {
+    let user_id = match ObjectId::parse_str(&request.user_id) {
         Ok(id) => id,
         Err(_) => {
-            return (
-                axum::http::StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "status": "error",
-                    "message": format!("Invalid user ID format: {}", request.user_id)
-                })),
-            )
+            return Error::InvalidId(format!("Invalid user ID format: {}", request.user_id))
                 .into_response();
         }
     };
@@ -94,89 +358,433 @@ pub async fn award_top_recipe_handler(
     let user = match state.db.find_user(&user_id).await {
         Ok(Some(u)) => u,
         Ok(None) => {
-            return (
-                axum::http::StatusCode::NOT_FOUND,
-                Json(json!({
-                    "status": "error",
-                    "message": format!("User not found: {}", request.user_id)
-                })),
-            )
-                .into_response();
+            return Error::NotFound(format!("User not found: {}", request.user_id)).into_response();
         }
         Err(e) => {
             tracing::error!("Failed to fetch user in award_top_recipe_handler: {}", e);
-            return (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "error",
-                    "message": format!("Database error: {}", e)
-                })),
-            )
-                .into_response();
+            return e.into_response();
         }
     };
 
-    match state.db.add_badge_to_user(&user_id, badge_name).await {
-        Ok(Some(true)) => {
+    let recipe_id = match request.recipe_id.as_deref().map(ObjectId::parse_str) {
+        None => None,
+        Some(Ok(id)) => Some(id),
+        Some(Err(_)) => {
+            return Error::InvalidId(format!(
+                "Invalid recipe ID format: {}",
+                request.recipe_id.unwrap_or_default()
+            ))
+            .into_response();
+        }
+    };
+
+    let period = match request.period.clone() {
+        Some(period) if !category.period().is_valid(&period) => {
+            return Error::Validation(format!(
+                "Invalid period for category {}: {}",
+                request.category, period
+            ))
+            .into_response();
+        }
+        Some(period) => period,
+        None => category.period_of(chrono::Utc::now()),
+    };
+
+    // Badges granted before win history existed are recorded as a legacy win first
+    // so they keep counting towards the total.
+    if user.has_unrecorded_win(badge_name) {
+        let legacy_award = TopRecipeAward {
+            badge: badge_name.to_string(),
+            period: LEGACY_PERIOD.to_string(),
+            recipe_id: None,
+            awarded_at: chrono::Utc::now(),
+        };
+        if let Err(e) = state
+            .db
+            .record_top_recipe_award(&user_id, &legacy_award)
+            .await
+        {
+            tracing::error!(
+                "Failed to record legacy win in award_top_recipe_handler: {}",
+                e
+            );
+        }
+    }
+
+    let award = TopRecipeAward {
+        badge: badge_name.to_string(),
+        period: period.clone(),
+        recipe_id,
+        awarded_at: chrono::Utc::now(),
+    };
+
+    match state.db.record_top_recipe_award(&user_id, &award).await {
+        Ok(Some(outcome)) if outcome.newly_awarded => {
             tracing::info!(
-                "Successfully awarded badge {} to user {}",
+                "Successfully awarded badge {} to user {} for period {} (win #{})",
                 badge_name,
-                request.user_id
+                request.user_id,
+                period,
+                outcome.win_count
             );
 
+            let mut new_tier_badges = Vec::new();
+            for tier_badge in top_recipe_tier_badges(badge_name, outcome.win_count) {
+                match state.db.add_badge_to_user(&user_id, &tier_badge).await {
+                    Ok(Some(true)) => new_tier_badges.push(tier_badge),
+                    Ok(_) => {}
+                    Err(e) => tracing::error!(
+                        "Failed to add tier badge {} in award_top_recipe_handler: {}",
+                        tier_badge,
+                        e
+                    ),
+                }
+            }
+
             // Send notification using state.notifier
             if let Some(ref email) = user.email {
                 let metadata = serde_json::json!({
                     "badgeName": badge_name,
-                    "userId": &request.user_id
+                    "userId": &request.user_id,
+                    "winCount": outcome.win_count
                 });
                 state
                     .notifier
                     .send_notification("NEW_BADGE", email, metadata)
                     .await;
+
+                for tier_badge in &new_tier_badges {
+                    let metadata = serde_json::json!({
+                        "badgeName": tier_badge,
+                        "userId": &request.user_id
+                    });
+                    state
+                        .notifier
+                        .send_notification("NEW_BADGE", email, metadata)
+                        .await;
+                }
             }
 
             Json(json!({
                 "status": "success",
                 "message": format!("Badge {} awarded successfully", badge_name),
-                "badge": badge_name
+                "badge": badge_name,
+                "period": period,
+                "win_count": outcome.win_count,
+                "tier_badges": new_tier_badges
             }))
             .into_response()
         }
-        Ok(Some(false)) => {
+        Ok(Some(outcome)) => {
             tracing::info!(
-                "User {} already has badge {}. Skipping award.",
+                "User {} already won badge {} for period {}. Skipping award.",
                 request.user_id,
-                badge_name
+                badge_name,
+                period
             );
             Json(json!({
                 "status": "already_awarded",
-                "message": "User already has this badge",
-                "badge": badge_name
+                "message": "User already has this badge for this period",
+                "badge": badge_name,
+                "period": period,
+                "win_count": outcome.win_count
             }))
             .into_response()
         }
-        Ok(None) => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(json!({
-                "status": "error",
-                "message": format!("User not found: {}", request.user_id)
-            })),
-        )
-            .into_response(),
+        Ok(None) => Error::NotFound(format!("User not found: {}", request.user_id)).into_response(),
         Err(e) => {
             tracing::error!(
                 "Failed to update user badges in award_top_recipe_handler: {}",
                 e
             );
-            (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "status": "error",
-                    "message": format!("Failed to update user badges: {}", e)
-                })),
+            e.into_response()
+        }
+    }
+}
+
+pub async fn verification_status_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    let user_oid = match ObjectId::parse_str(&user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return Error::InvalidId(format!("Invalid user ID format: {}", user_id))
+                .into_response();
+        }
+    };
+
+    let user = match state.db.find_user(&user_oid).await {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            return Error::NotFound(format!("User not found: {}", user_id)).into_response();
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch user in verification_status_handler: {}", e);
+            return e.into_response();
+        }
+    };
+
+    let stats = match state.db.get_user_stats(&user_oid).await {
+        Ok(stats) => stats,
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch recipe stats in verification_status_handler: {}",
+                e
+            );
+            return e.into_response();
+        }
+    };
+    let report = state
+        .verification_policy
+        .evaluate(&stats, user.created_at, chrono::Utc::now());
+
+    Json(json!({
+        "status": "ok",
+        "user_id": user_id,
+        "verified": user.verified.unwrap_or(false),
+        "eligible": report.eligible,
+        "missing": report.missing,
+        "stats": stats,
+        "policy": state.verification_policy
+    }))
+    .into_response()
+}
+
+pub async fn admin_verification_handler(
+    State(state): State<Arc<AppState>>,
+    Extension(key): Extension<AuthenticatedKey>,
+    Json(request): Json<VerificationOverrideRequest>,
+) -> impl IntoResponse {
+    tracing::info!("Verification override request: {:?}", request);
+
+    if request.reason.trim().is_empty() {
+        return Error::Validation("A reason is required to override verification".to_string())
+            .into_response();
+    }
+
+    let user_id = match ObjectId::parse_str(&request.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return Error::InvalidId(format!("Invalid user ID format: {}", request.user_id))
+                .into_response();
+        }
+    };
+
+    let user = match state.db.find_user(&user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            return Error::NotFound(format!("User not found: {}", request.user_id)).into_response();
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch user in admin_verification_handler: {}", e);
+            return e.into_response();
+        }
+    };
+
+    match state
+        .db
+        .set_verification(
+            &user_id,
+            request.verified,
+            request.locked,
+            &request.reason,
+            &key.name,
+        )
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return Error::NotFound(format!("User not found: {}", request.user_id)).into_response();
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to update verification in admin_verification_handler: {}",
+                e
+            );
+            return e.into_response();
+        }
+    }
+
+    tracing::info!(
+        "Verification of user {} set to {} (locked: {}) by {}: {}",
+        request.user_id,
+        request.verified,
+        request.locked,
+        key.name,
+        request.reason
+    );
+
+    let was_verified = user.verified.unwrap_or(false);
+    let notification_type = match (was_verified, request.verified) {
+        (false, true) => Some("VERIFIED"),
+        (true, false) => Some("UNVERIFIED"),
+        _ => None,
+    };
+
+    if let (Some(notification_type), Some(email)) = (notification_type, &user.email) {
+        let metadata = serde_json::json!({
+            "userId": &request.user_id,
+            "reason": &request.reason
+        });
+        state
+            .notifier
+            .send_notification(notification_type, email, metadata)
+            .await;
+    }
+
+    Json(json!({
+        "status": "success",
+        "user_id": request.user_id,
+        "verified": request.verified,
+        "locked": request.locked,
+        "reason": request.reason,
+        "updated_by": key.name
+    }))
+    .into_response()
+}
+
+pub async fn pending_reviews_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match state.db.get_pending_badge_reviews().await {
+        Ok(reviews) => Json(json!({
+            "status": "ok",
+            "pending_count": reviews.len(),
+            "reviews": reviews
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to fetch reviews in pending_reviews_handler: {}", e);
+            e.into_response()
+        }
+    }
+}
+
+pub async fn resolve_review_handler(
+    State(state): State<Arc<AppState>>,
+    Extension(key): Extension<AuthenticatedKey>,
+    Path(review_id): Path<String>,
+    Json(request): Json<ReviewDecisionRequest>,
+) -> impl IntoResponse {
+    let review_oid = match ObjectId::parse_str(&review_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return Error::InvalidId(format!("Invalid review ID format: {}", review_id))
+                .into_response();
+        }
+    };
+
+    let status = match request.action {
+        ReviewAction::Approve => ReviewStatus::Approved,
+        ReviewAction::Reject => ReviewStatus::Rejected,
+    };
+
+    let review = match state
+        .db
+        .resolve_badge_review(&review_oid, status, &key.name)
+        .await
+    {
+        Ok(Some(review)) => review,
+        Ok(None) => {
+            return Error::NotFound(format!("Pending review not found: {}", review_id))
+                .into_response();
+        }
+        Err(e) => {
+            tracing::error!("Failed to resolve review in resolve_review_handler: {}", e);
+            return e.into_response();
+        }
+    };
+
+    tracing::info!(
+        "Review {} for user {} resolved as {:?} by {}",
+        review_id,
+        review.user_id,
+        status,
+        key.name
+    );
+
+    if status == ReviewStatus::Rejected {
+        return Json(json!({
+            "status": "success",
+            "review": review
+        }))
+        .into_response();
+    }
+
+    let user = match state.db.find_user(&review.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => {
+            return Error::NotFound(format!("User not found: {}", review.user_id)).into_response();
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch user in resolve_review_handler: {}", e);
+            return e.into_response();
+        }
+    };
+
+    let mut granted_badges = Vec::new();
+    for badge in &review.badges {
+        match state.db.add_badge_to_user(&review.user_id, badge).await {
+            Ok(Some(true)) => granted_badges.push(badge.clone()),
+            Ok(_) => {}
+            Err(e) => tracing::error!(
+                "Failed to grant badge {} in resolve_review_handler: {}",
+                badge,
+                e
+            ),
+        }
+    }
+
+    // A verification lock set by an admin in the meantime takes precedence
+    let grant_verification = review.verify
+        && !user.verified.unwrap_or(false)
+        && !user.verification_locked.unwrap_or(false);
+    if grant_verification
+        && let Err(e) = state
+            .db
+            .set_verification(
+                &review.user_id,
+                true,
+                false,
+                "Approved after abuse review",
+                &key.name,
             )
-                .into_response()
+            .await
+    {
+        tracing::error!(
+            "Failed to grant verification in resolve_review_handler: {}",
+            e
+        );
+    }
+
+    if let Some(ref email) = user.email {
+        let user_id = review.user_id.to_hex();
+        for badge in &granted_badges {
+            let metadata = serde_json::json!({
+                "badgeName": badge,
+                "userId": &user_id
+            });
+            state
+                .notifier
+                .send_notification("NEW_BADGE", email, metadata)
+                .await;
+        }
+
+        if grant_verification {
+            let metadata = serde_json::json!({
+                "userId": &user_id
+            });
+            state
+                .notifier
+                .send_notification("VERIFIED", email, metadata)
+                .await;
         }
     }
+
+    Json(json!({
+        "status": "success",
+        "review": review,
+        "granted_badges": granted_badges,
+        "verified": grant_verification
+    }))
+    .into_response()
 }
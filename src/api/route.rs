@@ -1,26 +1,62 @@
 use axum::{
     Router,
-    middleware::from_fn,
+    middleware::from_fn_with_state,
     routing::{get, post},
 };
 use std::sync::Arc;
 
 use crate::api::{
     handler::{
-        award_top_recipe_handler, health_handler, queue_status_handler, update_badges_handler,
-        version_handler,
+        admin_verification_handler, award_top_recipe_handler, batch_update_handler, health_handler,
+        metrics_handler, pending_reviews_handler, queue_status_handler, readiness_handler,
+        request_status_handler, resolve_review_handler, update_badges_handler,
+        verification_status_handler, version_handler,
     },
     state::AppState,
 };
-use crate::middleware::auth::require_api_key;
+use crate::middleware::auth::{Scope, require_api_key, require_scope};
+use crate::middleware::rate_limit::{limit_per_key, limit_per_user};
+use crate::middleware::signature::verify_signature;
 
 pub fn create_router(state: Arc<AppState>) -> Router {
-    Router::new()
+    // The batch handler applies the per-user limit to each of its items
+    let enqueue = Router::new()
         .route("/update", post(update_badges_handler))
-        .route("/award-top-recipe", post(award_top_recipe_handler))
+        .route_layer(from_fn_with_state(state.clone(), limit_per_user))
+        .route("/update/batch", post(batch_update_handler));
+    let award = Router::new().route("/award-top-recipe", post(award_top_recipe_handler));
+    let read = Router::new()
         .route("/status", get(queue_status_handler))
-        .route_layer(from_fn(require_api_key))
+        .route("/verification/{user_id}", get(verification_status_handler))
+        .route("/requests/{request_id}", get(request_status_handler));
+    let admin = Router::new()
+        .route("/admin/verification", post(admin_verification_handler))
+        .route("/admin/reviews", get(pending_reviews_handler))
+        .route("/admin/reviews/{review_id}", post(resolve_review_handler));
+
+    Router::new()
+        .merge(scoped(Scope::Enqueue, enqueue))
+        .merge(scoped(Scope::Award, award))
+        .merge(scoped(Scope::Read, read))
+        .merge(scoped(Scope::Admin, admin))
+        .route_layer(from_fn_with_state(state.clone(), limit_per_key))
+        .route_layer(from_fn_with_state(state.clone(), require_api_key))
+        .route_layer(from_fn_with_state(state.clone(), verify_signature))
         .route("/health", get(health_handler))
+        .route("/health/live", get(health_handler))
+        .route("/health/ready", get(readiness_handler))
         .route("/version", get(version_handler))
         .with_state(state)
 }
+
+/// Router of the metrics port, kept off the public port so metrics need no API key.
+pub fn create_metrics_router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(state)
+}
+
+/// Requires `scope` for every route of `router`.
+fn scoped(scope: Scope, router: Router<Arc<AppState>>) -> Router<Arc<AppState>> {
+    router.route_layer(from_fn_with_state(scope, require_scope))
+}
@@ -1,11 +1,40 @@
 use std::sync::Arc;
 
+use crate::middleware::auth::ApiKeys;
+use crate::middleware::rate_limit::RateLimits;
+use crate::middleware::signature::SignatureVerifier;
+use crate::model::category::CategoryRegistry;
 use crate::queue::BadgeUpdateQueue;
-use crate::service::db::Database;
+use crate::service::connectivity::Connectivity;
+use crate::service::db::{CacheCounters, Database};
+use crate::service::heartbeat::Heartbeat;
+use crate::service::metrics::ProcessorMetrics;
 use crate::service::notifier::Notifier;
+use crate::service::shutdown::Shutdown;
+use crate::service::tracking::RequestTracker;
+use crate::service::verification::VerificationPolicy;
 
 pub struct AppState {
+    /// Keys clients may send in the `X-API-Key` header.
+    pub api_keys: ApiKeys,
+    /// Verifies requests signed with one of `api_keys` instead of sending it.
+    pub signatures: SignatureVerifier,
+    /// Request limits per API key and per user of `/update`.
+    pub rate_limits: RateLimits,
     pub badge_queue: Arc<dyn BadgeUpdateQueue>,
+    /// Most user ids `/update/batch` accepts in one request.
+    pub batch_max_size: usize,
     pub db: Arc<dyn Database>,
     pub notifier: Arc<dyn Notifier>,
+    pub categories: CategoryRegistry,
+    pub verification_policy: VerificationPolicy,
+    /// Hit/miss counters of the database cache, when it is enabled.
+    pub cache: Option<Arc<CacheCounters>>,
+    pub connectivity: Connectivity,
+    pub processor_heartbeat: Heartbeat,
+    pub processor_metrics: Arc<ProcessorMetrics>,
+    /// Recent badge update requests, looked up by `/requests/{request_id}`.
+    pub requests: RequestTracker,
+    /// Set once a shutdown signal arrives, after which `/update` is rejected.
+    pub shutdown: Shutdown,
 }
@@ -0,0 +1,65 @@
+use chrono::Utc;
+
+use crate::middleware::signature::{
+    KEY_ID_HEADER, NONCE_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER, canonical_request, sign,
+};
+
+/// Signs requests to badge_forge with an API key instead of sending the key itself.
+///
+/// ```
+/// use badge_forge::client::RequestSigner;
+///
+/// let signer = RequestSigner::new("jorbites", "s3cret");
+/// let body = br#"{"user_id":"6650f1c2a1b2c3d4e5f60718"}"#;
+/// let headers = signer.signed_headers("POST", "/update", body);
+/// // Send the body unchanged with these headers, e.g. via reqwest:
+/// // client.post(url).headers(...).body(body.to_vec())
+/// assert_eq!(headers.len(), 4);
+/// ```
+#[derive(Clone)]
+pub struct RequestSigner {
+    key_name: String,
+    secret: String,
+}
+
+impl RequestSigner {
+    /// `key_name` is the name of the API key whose `secret` signs the requests.
+    pub fn new(key_name: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            key_name: key_name.into(),
+            secret: secret.into(),
+        }
+    }
+
+    /// Headers authenticating a request sent now. `path_and_query` must match the request
+    /// exactly, e.g. `/verification/abc?refresh=true`, and `body` is the exact bytes sent.
+    pub fn signed_headers(
+        &self,
+        method: &str,
+        path_and_query: &str,
+        body: &[u8],
+    ) -> Vec<(&'static str, String)> {
+        let nonce = uuid::Uuid::new_v4().to_string();
+        self.signed_headers_at(method, path_and_query, body, Utc::now().timestamp(), &nonce)
+    }
+
+    /// Headers for a request signed at `timestamp` (Unix seconds) with the given `nonce`.
+    /// The server rejects a signature it has already seen, so every request needs its own
+    /// nonce.
+    pub fn signed_headers_at(
+        &self,
+        method: &str,
+        path_and_query: &str,
+        body: &[u8],
+        timestamp: i64,
+        nonce: &str,
+    ) -> Vec<(&'static str, String)> {
+        let canonical = canonical_request(method, path_and_query, timestamp, nonce, body);
+        vec![
+            (KEY_ID_HEADER, self.key_name.clone()),
+            (TIMESTAMP_HEADER, timestamp.to_string()),
+            (NONCE_HEADER, nonce.to_string()),
+            (SIGNATURE_HEADER, sign(&self.secret, &canonical)),
+        ]
+    }
+}
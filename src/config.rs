@@ -0,0 +1,330 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::middleware::auth::{ApiKey, ApiKeys};
+use crate::middleware::rate_limit::RateLimitConfig;
+use crate::middleware::signature::SignatureConfig;
+use crate::model::category::CategoryRegistry;
+use crate::service::abuse::AbuseDetector;
+use crate::service::connectivity::ConnectivityConfig;
+use crate::service::db::{CacheConfig, DatabaseBackend};
+use crate::service::indexes::IndexMode;
+use crate::service::schema::SchemaMapping;
+use crate::service::shutdown::ShutdownConfig;
+use crate::service::tracking::TrackingConfig;
+use crate::service::verification::VerificationPolicy;
+
+/// Publicly known API key, only accepted in insecure dev mode.
+pub const DEV_API_KEY: &str = "default_key";
+
+/// Where settings are read from: environment variables, falling back to the TOML file named
+/// by `CONFIG_FILE`.
+///
+/// The file is a flat table keyed by the lowercase variable names, e.g. `port = 4000` or
+/// `db_cache_ttl_secs = 5`.
+#[derive(Debug, Default)]
+pub struct ConfigSource {
+    file: toml::Table,
+    /// File keys that were looked up, to reject the ones nothing reads.
+    read: RefCell<BTreeSet<String>>,
+}
+
+impl ConfigSource {
+    /// Reads the file named by `CONFIG_FILE`, if set.
+    pub fn load() -> Result<Self, String> {
+        match std::env::var("CONFIG_FILE") {
+            Ok(path) if !path.trim().is_empty() => Self::from_file(Path::new(path.trim())),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_toml(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, String> {
+        let file: toml::Table = contents
+            .parse()
+            .map_err(|e: toml::de::Error| format!("Invalid TOML: {}", e.message()))?;
+        Ok(Self {
+            file,
+            read: RefCell::default(),
+        })
+    }
+
+    /// The raw value of a setting. Non-string values from the file are rendered as text,
+    /// arrays and tables as JSON.
+    pub fn var(&self, name: &str) -> Option<String> {
+        let key = name.to_ascii_lowercase();
+        // Recorded even when the environment overrides it, so the file key is not unknown
+        self.read.borrow_mut().insert(key.clone());
+        if let Ok(value) = std::env::var(name) {
+            return Some(value);
+        }
+        let value = self.file.get(&key)?;
+        Some(match value {
+            toml::Value::String(s) => s.clone(),
+            toml::Value::Integer(i) => i.to_string(),
+            toml::Value::Float(f) => f.to_string(),
+            toml::Value::Boolean(b) => b.to_string(),
+            toml::Value::Datetime(d) => d.to_string(),
+            toml::Value::Array(_) | toml::Value::Table(_) => toml_to_json(value).to_string(),
+        })
+    }
+
+    /// Reads an unsigned integer setting, falling back to `default` when unset.
+    pub fn u32(&self, name: &str, default: u32) -> Result<u32, String> {
+        match self.var(name) {
+            Some(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| format!("{} must be a non-negative integer, got {:?}", name, value)),
+            None => Ok(default),
+        }
+    }
+
+    /// Reads a boolean setting (`true`/`false`/`1`/`0`), falling back to `default` when unset.
+    pub fn bool(&self, name: &str, default: bool) -> Result<bool, String> {
+        match self.var(name) {
+            Some(value) => match value.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" => Ok(true),
+                "false" | "0" => Ok(false),
+                _ => Err(format!("{} must be true or false, got {:?}", name, value)),
+            },
+            None => Ok(default),
+        }
+    }
+
+    /// Reads a string setting, falling back to `default` when unset or blank.
+    pub fn string(&self, name: &str, default: &str) -> String {
+        self.optional_string(name)
+            .unwrap_or_else(|| default.to_string())
+    }
+
+    /// Reads a string setting, `None` when unset or blank.
+    pub fn optional_string(&self, name: &str) -> Option<String> {
+        self.var(name)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn port(&self, name: &str, default: u16) -> Result<u16, String> {
+        let port = self.u32(name, default as u32)?;
+        u16::try_from(port)
+            .ok()
+            .filter(|port| *port != 0)
+            .ok_or_else(|| format!("{} must be a valid port number, got {}", name, port))
+    }
+
+    /// Fails on file keys no setting has read, which are most likely typos.
+    pub fn check_unknown_keys(&self) -> Result<(), String> {
+        let read = self.read.borrow();
+        let unknown: Vec<&str> = self
+            .file
+            .keys()
+            .filter(|key| !read.contains(key.as_str()))
+            .map(String::as_str)
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Unknown settings: {}", unknown.join(", ")))
+        }
+    }
+}
+
+/// Where badge_forge stores its data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseConfig {
+    pub backend: DatabaseBackend,
+    pub mongodb_uri: String,
+    pub db_name: String,
+    /// Check the mapped fields against sample documents at startup.
+    pub validate_schema: bool,
+    pub indexes: IndexMode,
+    pub memory_fixture: Option<PathBuf>,
+    pub memory_path: Option<PathBuf>,
+    pub sqlite_path: PathBuf,
+    pub sqlite_fixture: Option<PathBuf>,
+}
+
+impl DatabaseConfig {
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        Ok(Self {
+            backend: DatabaseBackend::from_source(source)?,
+            mongodb_uri: source.string("MONGODB_URI", "mongodb://localhost:27017"),
+            db_name: source.string("DB_NAME", "badgeforge"),
+            validate_schema: source.bool("SCHEMA_VALIDATE", true)?,
+            indexes: IndexMode::from_source(source)?,
+            memory_fixture: source
+                .optional_string("MEMORY_DB_FIXTURE")
+                .map(PathBuf::from),
+            memory_path: source.optional_string("MEMORY_DB_PATH").map(PathBuf::from),
+            sqlite_path: PathBuf::from(source.string("SQLITE_PATH", "badge_forge.sqlite3")),
+            sqlite_fixture: source.optional_string("SQLITE_FIXTURE").map(PathBuf::from),
+        })
+    }
+}
+
+/// Endpoint badge awards are reported to. An empty URL disables notifications.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotifierConfig {
+    pub url: String,
+    pub api_key: String,
+}
+
+/// Axiom dataset logs are shipped to, in addition to stdout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AxiomConfig {
+    pub token: String,
+    pub dataset: String,
+}
+
+/// Every setting of the service, validated at startup.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub port: u16,
+    pub metrics_port: u16,
+    /// Badge update requests the queue holds before `/update` is rejected with 429.
+    pub queue_size: usize,
+    /// Requests processed concurrently.
+    pub worker_count: usize,
+    /// Most user ids `/update/batch` accepts in one request, at most `queue_size`.
+    pub batch_max_size: usize,
+    pub api_keys: ApiKeys,
+    /// Accepts `DEV_API_KEY` when no key is set, for local development only.
+    pub insecure_dev_mode: bool,
+    pub signatures: SignatureConfig,
+    pub rate_limits: RateLimitConfig,
+    pub database: DatabaseConfig,
+    pub schema: SchemaMapping,
+    pub cache: CacheConfig,
+    pub connectivity: ConnectivityConfig,
+    pub notifier: NotifierConfig,
+    pub axiom: Option<AxiomConfig>,
+    pub categories: CategoryRegistry,
+    pub verification: VerificationPolicy,
+    pub abuse: AbuseDetector,
+    pub watch_recipes: bool,
+    pub shutdown: ShutdownConfig,
+    pub tracking: TrackingConfig,
+}
+
+impl Config {
+    /// Reads the configuration from the environment and `CONFIG_FILE`.
+    pub fn load() -> Result<Self, String> {
+        let source = ConfigSource::load()?;
+        let config = Self::from_source(&source)?;
+        source.check_unknown_keys()?;
+        Ok(config)
+    }
+
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let queue_size = source.u32("QUEUE_SIZE", 100)? as usize;
+        if queue_size == 0 {
+            return Err("QUEUE_SIZE must be at least 1".to_string());
+        }
+        let worker_count = source.u32("WORKER_COUNT", 1)? as usize;
+        if worker_count == 0 {
+            return Err("WORKER_COUNT must be at least 1".to_string());
+        }
+        // A larger batch could never be queued in full
+        let batch_max_size = source.u32("BATCH_MAX_SIZE", queue_size as u32)? as usize;
+        if batch_max_size == 0 {
+            return Err("BATCH_MAX_SIZE must be at least 1".to_string());
+        }
+        if batch_max_size > queue_size {
+            return Err(format!(
+                "BATCH_MAX_SIZE ({}) must not exceed QUEUE_SIZE ({})",
+                batch_max_size, queue_size
+            ));
+        }
+        let insecure_dev_mode = source.bool("INSECURE_DEV_MODE", false)?;
+        let api_keys = api_keys_from_source(source, insecure_dev_mode)?;
+        let axiom = match (
+            source.optional_string("AXIOM_TOKEN"),
+            source.optional_string("AXIOM_DATASET"),
+        ) {
+            (Some(token), Some(dataset)) => Some(AxiomConfig { token, dataset }),
+            (None, None) => None,
+            _ => return Err("AXIOM_TOKEN and AXIOM_DATASET must be set together".to_string()),
+        };
+
+        Ok(Self {
+            port: source.port("PORT", 4000)?,
+            metrics_port: source.port("METRICS_PORT", 9091)?,
+            queue_size,
+            worker_count,
+            batch_max_size,
+            api_keys,
+            insecure_dev_mode,
+            signatures: SignatureConfig::from_source(source)?,
+            rate_limits: RateLimitConfig::from_source(source)?,
+            database: DatabaseConfig::from_source(source)?,
+            schema: SchemaMapping::from_source(source)?,
+            cache: CacheConfig::from_source(source)?,
+            connectivity: ConnectivityConfig::from_source(source)?,
+            notifier: NotifierConfig {
+                url: source.string("NOTIFIER_URL", ""),
+                api_key: source.string("NOTIFIER_API_KEY", ""),
+            },
+            axiom,
+            categories: CategoryRegistry::from_source(source)?,
+            verification: VerificationPolicy::from_source(source)?,
+            abuse: AbuseDetector::from_source(source)?,
+            watch_recipes: source.bool("WATCH_RECIPES", false)?,
+            shutdown: ShutdownConfig::from_source(source)?,
+            tracking: TrackingConfig::from_source(source)?,
+        })
+    }
+}
+
+/// Reads `API_KEY`, a key with every scope named `default`, and the named keys of `API_KEYS`.
+fn api_keys_from_source(source: &ConfigSource, insecure_dev_mode: bool) -> Result<ApiKeys, String> {
+    let mut keys = Vec::new();
+    if let Some(key) = source.optional_string("API_KEY") {
+        keys.push(ApiKey::with_all_scopes("default", key));
+    }
+    if let Some(raw) = source.optional_string("API_KEYS") {
+        let named: Vec<ApiKey> =
+            serde_json::from_str(&raw).map_err(|e| format!("Invalid API_KEYS: {}", e))?;
+        keys.extend(named);
+    }
+
+    if !insecure_dev_mode {
+        if let Some(key) = keys.iter().find(|key| key.key == DEV_API_KEY) {
+            return Err(format!(
+                "API key {} must not be the publicly known {:?}; set a secret key, or \
+                 INSECURE_DEV_MODE=true for local development",
+                key.name, DEV_API_KEY
+            ));
+        }
+        if keys.is_empty() {
+            return Err("No API key is set; set API_KEY or API_KEYS, or \
+                        INSECURE_DEV_MODE=true for local development"
+                .to_string());
+        }
+    } else if keys.is_empty() {
+        keys.push(ApiKey::with_all_scopes("dev", DEV_API_KEY));
+    }
+    ApiKeys::new(keys)
+}
+
+/// Converts a TOML value to JSON, with dates as RFC 3339 strings.
+fn toml_to_json(value: &toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(s) => serde_json::Value::from(s.as_str()),
+        toml::Value::Integer(i) => serde_json::Value::from(*i),
+        toml::Value::Float(f) => serde_json::Value::from(*f),
+        toml::Value::Boolean(b) => serde_json::Value::from(*b),
+        toml::Value::Datetime(d) => serde_json::Value::from(d.to_string()),
+        toml::Value::Array(items) => items.iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => table
+            .iter()
+            .map(|(key, value)| (key.clone(), toml_to_json(value)))
+            .collect(),
+    }
+}
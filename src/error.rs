@@ -0,0 +1,175 @@
+use std::fmt;
+use std::time::Duration;
+
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use mongodb::error::{ErrorKind, WriteFailure};
+use serde_json::json;
+
+/// Server error codes that go away on their own (failovers, shutdowns, timeouts).
+const TRANSIENT_SERVER_CODES: [i32; 10] = [6, 7, 89, 91, 189, 262, 9001, 10107, 11600, 11602];
+/// Server error codes signalling a concurrent write (write conflict, duplicate key).
+const CONFLICT_SERVER_CODES: [i32; 2] = [112, 11000];
+
+/// Errors returned by the database, the queue and the processor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The requested user, review or request does not exist.
+    NotFound(String),
+    /// An id is not a valid MongoDB ObjectId.
+    InvalidId(String),
+    /// Any other invalid input from the client.
+    Validation(String),
+    /// A failure that is expected to succeed when retried, e.g. a lost connection.
+    Transient(String),
+    /// A failure that retrying will not fix.
+    Permanent(String),
+    /// A concurrent write got in the way.
+    Conflict(String),
+    /// The client sent too many requests or the queue is full; retry after `retry_after`.
+    TooManyRequests {
+        message: String,
+        retry_after: Duration,
+    },
+}
+
+impl Error {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::InvalidId(_) | Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Transient(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Permanent(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    /// Whether the failed operation may succeed if attempted again.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Transient(_) | Self::Conflict(_) | Self::TooManyRequests { .. }
+        )
+    }
+
+    /// Message safe to return to API clients. Infrastructure details are only logged.
+    pub fn public_message(&self) -> String {
+        match self {
+            Self::NotFound(message)
+            | Self::InvalidId(message)
+            | Self::Validation(message)
+            | Self::Conflict(message)
+            | Self::TooManyRequests { message, .. } => message.clone(),
+            Self::Transient(_) => "Service temporarily unavailable, please retry".to_string(),
+            Self::Permanent(_) => "Internal server error".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(message) => write!(f, "Not found: {}", message),
+            Self::InvalidId(message) => write!(f, "Invalid id: {}", message),
+            Self::Validation(message) => write!(f, "Validation error: {}", message),
+            Self::Transient(message) => write!(f, "Transient error: {}", message),
+            Self::Permanent(message) => write!(f, "Permanent error: {}", message),
+            Self::Conflict(message) => write!(f, "Conflict: {}", message),
+            Self::TooManyRequests {
+                message,
+                retry_after,
+            } => write!(
+                f,
+                "Too many requests: {} (retry after {}s)",
+                message,
+                retry_after.as_secs()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<mongodb::error::Error> for Error {
+    fn from(error: mongodb::error::Error) -> Self {
+        let message = format!("Database error: {}", error);
+
+        let transient_label = ["RetryableWriteError", "TransientTransactionError"]
+            .iter()
+            .any(|label| error.contains_label(label));
+        let server_code = match error.kind.as_ref() {
+            ErrorKind::Command(command) => Some(command.code),
+            ErrorKind::Write(WriteFailure::WriteError(write)) => Some(write.code),
+            ErrorKind::Write(WriteFailure::WriteConcernError(concern)) => Some(concern.code),
+            _ => None,
+        };
+
+        match error.kind.as_ref() {
+            ErrorKind::Io(_)
+            | ErrorKind::ServerSelection { .. }
+            | ErrorKind::ConnectionPoolCleared { .. }
+            | ErrorKind::DnsResolve { .. } => Self::Transient(message),
+            _ if transient_label => Self::Transient(message),
+            _ => match server_code {
+                Some(code) if CONFLICT_SERVER_CODES.contains(&code) => Self::Conflict(message),
+                Some(code) if TRANSIENT_SERVER_CODES.contains(&code) => Self::Transient(message),
+                _ => Self::Permanent(message),
+            },
+        }
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        let message = format!("Database error: {}", error);
+        match error.sqlite_error_code() {
+            Some(rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked) => {
+                Self::Transient(message)
+            }
+            Some(rusqlite::ErrorCode::ConstraintViolation) => Self::Conflict(message),
+            _ => Self::Permanent(message),
+        }
+    }
+}
+
+impl From<mongodb::bson::ser::Error> for Error {
+    fn from(error: mongodb::bson::ser::Error) -> Self {
+        Self::Permanent(format!("Serialization error: {}", error))
+    }
+}
+
+impl From<mongodb::bson::de::Error> for Error {
+    fn from(error: mongodb::bson::de::Error) -> Self {
+        Self::Permanent(format!("Deserialization error: {}", error))
+    }
+}
+
+/// Value of a `Retry-After` header: whole seconds, rounded up so clients never retry too
+/// early, and at least 1.
+pub fn retry_after_secs(retry_after: Duration) -> u64 {
+    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    seconds.max(1)
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let mut response = (
+            self.status_code(),
+            Json(json!({
+                "status": "error",
+                "message": self.public_message()
+            })),
+        )
+            .into_response();
+        if let Self::TooManyRequests { retry_after, .. } = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, retry_after_secs(retry_after).into());
+        }
+        response
+    }
+}
@@ -1,4 +1,7 @@
 pub mod api;
+pub mod client;
+pub mod config;
+pub mod error;
 pub mod middleware;
 pub mod model;
 pub mod queue;
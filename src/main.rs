@@ -1,63 +1,357 @@
-mod api;
-mod middleware;
-mod model;
-mod queue;
-mod service;
-mod utils;
-use crate::queue::BadgeUpdateQueue;
-use api::route::create_router;
-use api::state::AppState;
+use badge_forge::api::route::{create_metrics_router, create_router};
+use badge_forge::api::state::AppState;
+use badge_forge::config::{Config, DatabaseConfig};
+use badge_forge::middleware::rate_limit::RateLimits;
+use badge_forge::middleware::signature::SignatureVerifier;
+use badge_forge::queue::{BadgeUpdateQueue, InMemoryQueue};
+use badge_forge::service::badge_processor::BadgeForgeProcessor;
+use badge_forge::service::change_stream::RecipeWatcher;
+use badge_forge::service::connectivity::{Connectivity, ConnectivityMonitor};
+use badge_forge::service::db::{
+    CachedDatabase, Database, DatabaseBackend, MemoryDatabase, MemorySnapshot, MongoDatabase,
+    SqliteDatabase,
+};
+use badge_forge::service::heartbeat::Heartbeat;
+use badge_forge::service::indexes::IndexMode;
+use badge_forge::service::metrics::ProcessorMetrics;
+use badge_forge::service::schema::SchemaMapping;
+use badge_forge::service::shutdown::{self, Shutdown};
+use badge_forge::service::tracking::RequestTracker;
+use badge_forge::{service, utils};
 use dotenv::dotenv;
 use mongodb::{Client, options::ClientOptions};
-use queue::InMemoryQueue;
-use service::badge_processor::BadgeForgeProcessor;
+use std::path::Path;
 use std::sync::Arc;
-use tracing::info;
+use std::time::Duration;
+use tracing::{error, info, warn};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
 
-    utils::logging::init_logging("badge_forge");
+    let config = Config::load().map_err(|e| format!("Invalid configuration: {}", e))?;
+    let logs = utils::logging::init_logging("badge_forge", config.axiom.as_ref());
 
     info!("Starting Badge Forge API");
+    if config.insecure_dev_mode {
+        warn!("INSECURE_DEV_MODE is enabled, do not use it in production");
+    }
+    for key in config.api_keys.iter() {
+        match key.expires_at {
+            Some(expires_at) => info!(
+                "Accepting API key {} with scopes {:?} until {}",
+                key.name, key.scopes, expires_at
+            ),
+            None => info!(
+                "Accepting API key {} with scopes {:?}",
+                key.name, key.scopes
+            ),
+        }
+    }
 
-    let mongodb_uri =
-        std::env::var("MONGODB_URI").unwrap_or_else(|_| "mongodb://localhost:27017".to_string());
-    let db_name = std::env::var("DB_NAME").unwrap_or_else(|_| "badgeforge".to_string());
+    if config.signatures.required {
+        info!("Only accepting signed requests");
+    }
 
-    let client_options = ClientOptions::parse(mongodb_uri).await?;
-    let db_client = Client::with_options(client_options)?;
-
-    // Ping the database to verify connection
-    db_client
-        .database("admin")
-        .run_command(mongodb::bson::doc! { "ping": 1 })
-        .await?;
-    info!("Connected to MongoDB");
-
-    let (queue, receiver) = InMemoryQueue::new(100);
-    let queue_arc = Arc::new(queue);
+    let tracker = RequestTracker::new(config.tracking);
+    let (queue, receiver) = InMemoryQueue::new(config.queue_size);
+    let queue_arc = Arc::new(queue.with_tracker(tracker.clone()));
     let badge_queue = queue_arc.clone() as Arc<dyn BadgeUpdateQueue>;
 
-    let db = Arc::new(service::db::MongoDatabase::new(
-        db_client.clone(),
-        db_name.clone(),
-    )) as Arc<dyn service::db::Database>;
+    let (mut db, mongo) = match config.database.backend {
+        DatabaseBackend::Mongo => {
+            let mongo = MongoSetup::new(&config.database, &config.schema).await?;
+            (mongo.db.clone() as Arc<dyn Database>, Some(mongo))
+        }
+        DatabaseBackend::Memory => (
+            Arc::new(open_memory_database(&config.database)?) as Arc<dyn Database>,
+            None,
+        ),
+        DatabaseBackend::Sqlite => (
+            Arc::new(open_sqlite_database(&config.database)?) as Arc<dyn Database>,
+            None,
+        ),
+    };
+
+    let cache_config = config.cache;
+    let cache = if cache_config.is_enabled() {
+        let cached = CachedDatabase::new(db, cache_config);
+        let counters = cached.counters();
+        db = Arc::new(cached);
+        info!(
+            "Caching users and recipe aggregates for {:?} (at most {} users)",
+            cache_config.ttl, cache_config.max_users
+        );
+        Some(counters)
+    } else {
+        None
+    };
 
-    let notifier = Arc::new(service::notifier::HttpNotifier::from_env())
-        as Arc<dyn service::notifier::Notifier>;
+    let notifier = Arc::new(service::notifier::HttpNotifier::from_config(
+        &config.notifier,
+    )) as Arc<dyn service::notifier::Notifier>;
 
-    let processor = BadgeForgeProcessor::new(db.clone(), notifier.clone());
+    // The service starts before the database is reachable and reports not ready until it is
+    let connectivity = Connectivity::new(false);
+    let processor_heartbeat = Heartbeat::new();
+    let processor_metrics = Arc::new(ProcessorMetrics::default());
+    let processor = BadgeForgeProcessor::new(db.clone(), notifier.clone())
+        .with_verification_policy(config.verification)
+        .with_abuse_detector(config.abuse)
+        .with_connectivity(connectivity.clone())
+        .with_heartbeat(processor_heartbeat.clone())
+        .with_metrics(processor_metrics.clone())
+        .with_tracker(tracker.clone())
+        .with_worker_count(config.worker_count);
     processor.start(receiver, queue_arc.clone()).await;
 
+    let shutdown_config = config.shutdown.clone();
+    if let Some(path) = &shutdown_config.pending_path {
+        restore_pending_requests(path, badge_queue.clone())?;
+    }
+
+    let watch_recipes = config.watch_recipes;
+    if watch_recipes && mongo.is_none() {
+        warn!("WATCH_RECIPES is only supported with the MongoDB backend");
+    }
+
+    let monitor = ConnectivityMonitor::new(db.clone(), connectivity.clone(), config.connectivity);
+    let schema = config.schema.clone();
+    let watcher_queue = badge_queue.clone();
+    let monitor_connectivity = connectivity.clone();
+    tokio::spawn(async move {
+        if let Err(e) = monitor.wait_for_startup().await {
+            error!("Giving up on the database: {}", e);
+            std::process::exit(1);
+        }
+        if let Some(mongo) = mongo {
+            if let Err(e) = mongo.prepare().await {
+                error!("MongoDB setup failed: {}", e);
+                std::process::exit(1);
+            }
+            if watch_recipes {
+                RecipeWatcher::new(mongo.client, mongo.db_name, watcher_queue)
+                    .with_schema(schema)
+                    .start()
+                    .await;
+            }
+        }
+        monitor_connectivity.set_ready(true);
+        monitor.run().await;
+    });
+
     let state = Arc::new(AppState {
-        badge_queue,
+        api_keys: config.api_keys.clone(),
+        signatures: SignatureVerifier::new(config.signatures),
+        rate_limits: RateLimits::new(config.rate_limits),
+        requests: tracker,
+        badge_queue: badge_queue.clone(),
+        batch_max_size: config.batch_max_size,
         db,
         notifier,
+        categories: config.categories.clone(),
+        verification_policy: config.verification,
+        cache,
+        connectivity,
+        processor_heartbeat,
+        processor_metrics,
+        shutdown: Shutdown::new(),
     });
+
+    let metrics_listener = tokio::net::TcpListener::bind(("0.0.0.0", config.metrics_port)).await?;
+    let metrics_app = create_metrics_router(state.clone());
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(metrics_listener, metrics_app).await {
+            error!("Metrics server failed: {}", e);
+        }
+    });
+
+    // On a signal, reject new updates and let the processor drain the queue while the server
+    // keeps answering health checks, then stop the server
+    let draining = {
+        let shutdown = state.shutdown.clone();
+        let queue = badge_queue.clone();
+        let timeout = shutdown_config.timeout;
+        tokio::spawn(async move {
+            shutdown::shutdown_signal().await;
+            shutdown.trigger();
+            info!("Draining the badge queue for up to {:?}", timeout);
+            shutdown::drain_queue(queue.as_ref(), timeout).await;
+        })
+    };
+
     let app = create_router(state);
-    info!("Badge Forge API started successfully on port 4000 🎖️");
-    axum::serve(tokio::net::TcpListener::bind("0.0.0.0:4000").await?, app).await?;
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", config.port)).await?;
+    info!(
+        "Badge Forge API started successfully on port {} 🎖️",
+        config.port
+    );
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = draining.await;
+        })
+        .await?;
+
+    // Includes updates accepted by requests that were in flight when the drain ended
+    let pending = shutdown::drain_queue(badge_queue.as_ref(), Duration::ZERO).await;
+    if pending.is_empty() {
+        info!("Badge queue drained");
+    } else if let Some(path) = &shutdown_config.pending_path {
+        match shutdown::save_pending_requests(path, &pending) {
+            Ok(()) => info!(
+                "Saved {} pending badge updates to {}",
+                pending.len(),
+                path.display()
+            ),
+            Err(e) => error!("{}", e),
+        }
+    } else {
+        for request in &pending {
+            warn!(
+                "Dropping badge update {} for user {} (PENDING_REQUESTS_PATH is not set)",
+                request.request_id, request.user_id
+            );
+        }
+    }
+
+    info!("Badge Forge API stopped");
+    logs.flush().await;
     Ok(())
 }
+
+/// Re-enqueues the badge updates saved by the previous shutdown. Enqueued in the background,
+/// since there may be more than the queue holds before the processor takes them.
+fn restore_pending_requests(
+    path: &Path,
+    queue: Arc<dyn BadgeUpdateQueue>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let requests = shutdown::take_pending_requests(path)?;
+    if requests.is_empty() {
+        return Ok(());
+    }
+    info!(
+        "Restoring {} pending badge updates from {}",
+        requests.len(),
+        path.display()
+    );
+    tokio::spawn(async move {
+        for request in requests {
+            if let Err(e) = queue.enqueue(request).await {
+                error!("Failed to restore pending badge update: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// MongoDB client and database, created without connecting so startup does not depend on
+/// MongoDB being reachable.
+struct MongoSetup {
+    client: Client,
+    db_name: String,
+    db: Arc<MongoDatabase>,
+    validate_schema: bool,
+    index_mode: IndexMode,
+}
+
+impl MongoSetup {
+    async fn new(
+        config: &DatabaseConfig,
+        schema: &SchemaMapping,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let db_name = config.db_name.clone();
+        let client_options = ClientOptions::parse(&config.mongodb_uri).await?;
+        let client = Client::with_options(client_options)?;
+        let db = MongoDatabase::new(client.clone(), db_name.clone()).with_schema(schema.clone());
+
+        Ok(Self {
+            client,
+            db_name,
+            db: Arc::new(db),
+            validate_schema: config.validate_schema,
+            index_mode: config.indexes,
+        })
+    }
+
+    /// Validates the schema mapping and manages indexes, once MongoDB is reachable.
+    async fn prepare(&self) -> Result<(), badge_forge::error::Error> {
+        info!("Connected to MongoDB");
+        if self.validate_schema {
+            for field in self.db.validate_schema().await? {
+                if field.is_missing() || field.is_partial() {
+                    warn!(
+                        "Field {}.{} found in {} of {} sampled documents",
+                        field.collection, field.field, field.present, field.sampled
+                    );
+                }
+            }
+        }
+        let index_report = self.db.ensure_indexes(self.index_mode).await?;
+        if !index_report.created.is_empty() {
+            info!("Created indexes: {:?}", index_report.created);
+        }
+        if !index_report.missing.is_empty() {
+            warn!(
+                "Missing indexes (MONGODB_INDEXES=check): {:?}",
+                index_report.missing
+            );
+        }
+        for (index, reason) in &index_report.drifted {
+            warn!("Index {} differs from its declaration: {}", index, reason);
+        }
+        Ok(())
+    }
+}
+
+/// Builds the in-memory database, seeded from `MEMORY_DB_FIXTURE` and persisted to
+/// `MEMORY_DB_PATH` when set.
+fn open_memory_database(
+    config: &DatabaseConfig,
+) -> Result<MemoryDatabase, Box<dyn std::error::Error>> {
+    let mut db = match &config.memory_fixture {
+        Some(fixture) => {
+            let snapshot = MemorySnapshot::load(fixture)?;
+            info!(
+                "Seeded in-memory database from {} ({} users, {} recipes)",
+                fixture.display(),
+                snapshot.users.len(),
+                snapshot.recipes.len()
+            );
+            MemoryDatabase::from_snapshot(snapshot)
+        }
+        None => MemoryDatabase::new(),
+    };
+    if let Some(path) = &config.memory_path {
+        db = db.with_persistence(path.clone())?;
+        info!("Persisting in-memory database to {}", path.display());
+    }
+    warn!("Using the in-memory database backend, intended for local development only");
+    Ok(db)
+}
+
+/// Opens the SQLite database at `SQLITE_PATH`, seeding a new, empty database from
+/// `SQLITE_FIXTURE` when set.
+fn open_sqlite_database(
+    config: &DatabaseConfig,
+) -> Result<SqliteDatabase, Box<dyn std::error::Error>> {
+    let path = &config.sqlite_path;
+    let db = SqliteDatabase::open(path)?;
+    info!(
+        "Opened SQLite database {} (schema version {})",
+        path.display(),
+        db.schema_version()?
+    );
+    if let Some(fixture) = &config.sqlite_fixture
+        && db.is_empty()?
+    {
+        let snapshot = MemorySnapshot::load(fixture)?;
+        db.import(&snapshot)?;
+        info!(
+            "Seeded SQLite database from {} ({} users, {} recipes)",
+            fixture.display(),
+            snapshot.users.len(),
+            snapshot.recipes.len()
+        );
+    }
+    Ok(db)
+}
@@ -1,23 +1,203 @@
 use axum::{
-    extract::Request,
+    extract::{Request, State},
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
 };
-use std::env;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tracing::{Instrument, warn};
 
-pub async fn require_api_key(request: Request, next: Next) -> Response {
-    let api_key = env::var("API_KEY").unwrap_or_else(|_| "default_key".to_string());
+use crate::api::state::AppState;
 
-    #[allow(clippy::collapsible_if)] // Because grouping lets it is unstable
-    if let Some(auth_header) = request.headers().get("X-API-Key") {
-        if let Ok(header_value) = auth_header.to_str() {
-            // Check if the API key matches
-            if header_value == api_key {
-                return next.run(request).await;
+/// What an API key may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Enqueue badge updates with `/update`.
+    Enqueue,
+    /// Award top recipe badges with `/award-top-recipe`.
+    Award,
+    /// Override verification and resolve badge reviews under `/admin`.
+    Admin,
+    /// Read the queue status and verification reports.
+    Read,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 4] = [Scope::Enqueue, Scope::Award, Scope::Admin, Scope::Read];
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Scope::Enqueue => "enqueue",
+            Scope::Award => "award",
+            Scope::Admin => "admin",
+            Scope::Read => "read",
+        })
+    }
+}
+
+/// A named API key and what it may do. Several keys may share a name, so a client can
+/// switch to a new key while the old one still works.
+#[derive(Clone, Deserialize)]
+pub struct ApiKey {
+    pub name: String,
+    pub key: String,
+    pub scopes: Vec<Scope>,
+    /// When the key stops being accepted, to retire the old key after a rotation.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    pub fn with_all_scopes(name: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            key: key.into(),
+            scopes: Scope::ALL.to_vec(),
+            expires_at: None,
+        }
+    }
+
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKey")
+            .field("name", &self.name)
+            .field("key", &"<redacted>")
+            .field("scopes", &self.scopes)
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+/// The API keys accepted by `require_api_key`.
+#[derive(Debug, Clone)]
+pub struct ApiKeys {
+    keys: Vec<ApiKey>,
+}
+
+impl ApiKeys {
+    /// Rejects keys without a name, secret or scope, and secrets used by two keys.
+    pub fn new(keys: Vec<ApiKey>) -> Result<Self, String> {
+        if keys.is_empty() {
+            return Err("At least one API key is required".to_string());
+        }
+        let mut secrets = HashSet::new();
+        for key in &keys {
+            if key.name.trim().is_empty() {
+                return Err("API keys need a name".to_string());
+            }
+            if key.key.trim().is_empty() {
+                return Err(format!("API key {} has an empty key", key.name));
+            }
+            if key.scopes.is_empty() {
+                return Err(format!("API key {} has no scopes", key.name));
+            }
+            if !secrets.insert(key.key.as_str()) {
+                return Err(format!(
+                    "API key {} reuses the key of another entry",
+                    key.name
+                ));
             }
         }
+        Ok(Self { keys })
     }
 
-    (StatusCode::UNAUTHORIZED, "Invalid or missing API key").into_response()
+    pub fn iter(&self) -> impl Iterator<Item = &ApiKey> {
+        self.keys.iter()
+    }
+
+    /// Finds the unexpired key matching `presented`. Every key is compared in constant
+    /// time, so response times reveal neither how much of a guess matched nor which key.
+    /// Digests are compared rather than the keys, which would leak their length.
+    pub fn authenticate(&self, presented: &str, now: DateTime<Utc>) -> Option<&ApiKey> {
+        let presented = Sha256::digest(presented.as_bytes());
+        let mut found = None;
+        for key in &self.keys {
+            if bool::from(Sha256::digest(key.key.as_bytes()).ct_eq(&presented)) {
+                found = Some(key);
+            }
+        }
+        found.filter(|key| key.is_active(now))
+    }
+
+    /// The unexpired keys named `name`, used to verify signed requests.
+    pub fn active<'a>(
+        &'a self,
+        name: &'a str,
+        now: DateTime<Utc>,
+    ) -> impl Iterator<Item = &'a ApiKey> {
+        self.keys
+            .iter()
+            .filter(move |key| key.name == name && key.is_active(now))
+    }
+}
+
+/// The key a request was authenticated with, added to the request extensions.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedKey {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+}
+
+/// Authenticates the `X-API-Key` header, unless `verify_signature` already authenticated
+/// the request. Logs of the request carry the key name in the `api_key` field.
+pub async fn require_api_key(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if let Some(key) = request.extensions().get::<AuthenticatedKey>() {
+        let span = tracing::info_span!("request", api_key = %key.name);
+        return next.run(request).instrument(span).await;
+    }
+
+    let key = request
+        .headers()
+        .get("X-API-Key")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| state.api_keys.authenticate(value, Utc::now()));
+    let Some(key) = key else {
+        return (StatusCode::UNAUTHORIZED, "Invalid or missing API key").into_response();
+    };
+
+    let span = tracing::info_span!("request", api_key = %key.name);
+    request.extensions_mut().insert(AuthenticatedKey {
+        name: key.name.clone(),
+        scopes: key.scopes.clone(),
+    });
+    next.run(request).instrument(span).await
+}
+
+/// Rejects requests whose key lacks `scope`. Runs after `require_api_key`.
+pub async fn require_scope(State(scope): State<Scope>, request: Request, next: Next) -> Response {
+    match request.extensions().get::<AuthenticatedKey>() {
+        Some(key) if key.scopes.contains(&scope) => next.run(request).await,
+        Some(key) => {
+            warn!(
+                "API key {} lacks the {} scope for {}",
+                key.name,
+                scope,
+                request.uri().path()
+            );
+            (
+                StatusCode::FORBIDDEN,
+                format!("API key lacks the {} scope", scope),
+            )
+                .into_response()
+        }
+        None => (StatusCode::UNAUTHORIZED, "Invalid or missing API key").into_response(),
+    }
 }
@@ -0,0 +1,216 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::api::state::AppState;
+use crate::config::ConfigSource;
+use crate::error::Error;
+use crate::middleware::auth::AuthenticatedKey;
+
+/// Largest `/update` body accepted; a user id fits many times over.
+const MAX_UPDATE_BODY_BYTES: usize = 64 * 1024;
+/// Buckets kept before full ones are dropped, which bounds memory with many user ids.
+const PRUNE_THRESHOLD: usize = 10_000;
+/// Buckets kept at most, even when all of them are in use. Past it the least recently used
+/// tenth is dropped, which lets those keys burst again.
+pub const MAX_BUCKETS: usize = 100_000;
+
+/// Request limits, read from `RATE_LIMIT_PER_KEY` and `RATE_LIMIT_PER_USER`. Both are
+/// requests per minute, 0 disables the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Requests each API key may make to authenticated endpoints.
+    pub per_key: u32,
+    /// Badge updates that may be requested for each user.
+    pub per_user: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_key: 600,
+            per_user: 10,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let defaults = Self::default();
+        Ok(Self {
+            per_key: source.u32("RATE_LIMIT_PER_KEY", defaults.per_key)?,
+            per_user: source.u32("RATE_LIMIT_PER_USER", defaults.per_user)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    /// Size at which the next new key prunes, doubled when pruning frees little so a map of
+    /// busy buckets is not scanned on every request.
+    prune_at: usize,
+}
+
+impl Default for Buckets {
+    fn default() -> Self {
+        Self {
+            by_key: HashMap::new(),
+            prune_at: PRUNE_THRESHOLD,
+        }
+    }
+}
+
+/// Token bucket per key: allows bursts of `per_minute` requests, refilled evenly over a
+/// minute.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    /// A limiter allowing `per_minute` requests per key, unlimited when 0.
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// Number of keys a bucket is kept for.
+    pub fn tracked_keys(&self) -> usize {
+        self.lock().by_key.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Buckets> {
+        // A poisoned map only holds counters, which stay valid
+        self.buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Takes a request from the bucket of `key`, or returns how long until one is available.
+    pub fn check(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        if self.per_minute == 0 {
+            return Ok(());
+        }
+        let capacity = self.per_minute as f64;
+        let per_second = capacity / 60.0;
+        let mut buckets = self.lock();
+        if !buckets.by_key.contains_key(key) && buckets.by_key.len() >= buckets.prune_at {
+            buckets.by_key.retain(|_, bucket| {
+                bucket.tokens
+                    + now.saturating_duration_since(bucket.updated).as_secs_f64() * per_second
+                    < capacity
+            });
+            if buckets.by_key.len() >= MAX_BUCKETS {
+                let excess = buckets.by_key.len() - (MAX_BUCKETS - MAX_BUCKETS / 10);
+                let mut by_age: Vec<(Instant, String)> = buckets
+                    .by_key
+                    .iter()
+                    .map(|(key, bucket)| (bucket.updated, key.clone()))
+                    .collect();
+                by_age.select_nth_unstable(excess - 1);
+                for (_, key) in &by_age[..excess] {
+                    buckets.by_key.remove(key);
+                }
+            }
+            buckets.prune_at = (buckets.by_key.len() * 2).clamp(PRUNE_THRESHOLD, MAX_BUCKETS);
+        }
+
+        let bucket = buckets.by_key.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        }
+    }
+}
+
+/// The limiters of `RateLimitConfig`. The default imposes no limits.
+#[derive(Debug, Default)]
+pub struct RateLimits {
+    pub per_key: RateLimiter,
+    pub per_user: RateLimiter,
+}
+
+impl RateLimits {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            per_key: RateLimiter::new(config.per_key),
+            per_user: RateLimiter::new(config.per_user),
+        }
+    }
+}
+
+/// Limits the requests of each API key. Runs after `require_api_key`.
+pub async fn limit_per_key(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(key) = request.extensions().get::<AuthenticatedKey>()
+        && let Err(retry_after) = state.rate_limits.per_key.check(&key.name, Instant::now())
+    {
+        warn!("API key {} exceeded its rate limit", key.name);
+        return Error::TooManyRequests {
+            message: "Rate limit exceeded for this API key".to_string(),
+            retry_after,
+        }
+        .into_response();
+    }
+    next.run(request).await
+}
+
+/// Limits the badge updates requested for each user, read from the `user_id` of the body.
+/// Bodies without one are passed on for the handler to reject.
+pub async fn limit_per_user(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_UPDATE_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => {
+            return (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response();
+        }
+    };
+
+    let user_id = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| value.get("user_id")?.as_str().map(str::to_string));
+    if let Some(user_id) = user_id
+        && let Err(retry_after) = state.rate_limits.per_user.check(&user_id, Instant::now())
+    {
+        warn!("Rate limit exceeded for badge updates of user {}", user_id);
+        return Error::TooManyRequests {
+            message: format!("Too many badge updates requested for user {}", user_id),
+            retry_after,
+        }
+        .into_response();
+    }
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
@@ -0,0 +1,219 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::api::state::AppState;
+use crate::config::ConfigSource;
+use crate::middleware::auth::AuthenticatedKey;
+
+pub const KEY_ID_HEADER: &str = "X-Key-Id";
+pub const TIMESTAMP_HEADER: &str = "X-Timestamp";
+pub const NONCE_HEADER: &str = "X-Nonce";
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Largest body a signed request may have, since it is buffered to verify the signature.
+const MAX_SIGNED_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Settings of request signing, read from `SIGNATURE_MAX_AGE_SECS` and
+/// `REQUIRE_SIGNED_REQUESTS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureConfig {
+    /// How far the timestamp of a signed request may be from the server clock, either way.
+    pub max_age: Duration,
+    /// Rejects requests authenticated with a plain `X-API-Key` header.
+    pub required: bool,
+}
+
+impl Default for SignatureConfig {
+    fn default() -> Self {
+        Self {
+            max_age: Duration::from_secs(300),
+            required: false,
+        }
+    }
+}
+
+impl SignatureConfig {
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let defaults = Self::default();
+        let max_age = source.u32("SIGNATURE_MAX_AGE_SECS", defaults.max_age.as_secs() as u32)?;
+        if max_age == 0 {
+            return Err("SIGNATURE_MAX_AGE_SECS must be at least 1".to_string());
+        }
+        Ok(Self {
+            max_age: Duration::from_secs(max_age as u64),
+            required: source.bool("REQUIRE_SIGNED_REQUESTS", defaults.required)?,
+        })
+    }
+}
+
+/// The text a request signature covers. The body is included as its SHA-256 digest.
+pub fn canonical_request(
+    method: &str,
+    path_and_query: &str,
+    timestamp: i64,
+    nonce: &str,
+    body: &[u8],
+) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n{}",
+        method.to_ascii_uppercase(),
+        path_and_query,
+        timestamp,
+        nonce,
+        hex::encode(Sha256::digest(body))
+    )
+}
+
+/// Hex encoded HMAC-SHA256 of `canonical_request` with `secret`.
+pub fn sign(secret: &str, canonical_request: &str) -> String {
+    let mut mac = new_mac(secret);
+    mac.update(canonical_request.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn new_mac(secret: &str) -> HmacSha256 {
+    HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length")
+}
+
+/// Verifies signed requests and remembers the signatures seen within the replay window.
+#[derive(Debug, Default)]
+pub struct SignatureVerifier {
+    config: SignatureConfig,
+    seen: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl SignatureVerifier {
+    pub fn new(config: SignatureConfig) -> Self {
+        Self {
+            config,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `timestamp` (Unix seconds) is within `max_age` of `now`.
+    pub fn is_fresh(&self, timestamp: i64, now: DateTime<Utc>) -> bool {
+        now.timestamp().abs_diff(timestamp) <= self.config.max_age.as_secs()
+    }
+
+    /// Records a signature, returning false if it was already used within the window.
+    fn remember(&self, signature: &str, now: DateTime<Utc>) -> bool {
+        let max_age =
+            chrono::Duration::from_std(self.config.max_age).unwrap_or(chrono::Duration::MAX);
+        // A poisoned map only holds timestamps, which stay valid
+        let mut seen = self
+            .seen
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Outside the window the timestamp check rejects replays, so old entries can go
+        seen.retain(|_, at| now - *at <= max_age * 2);
+        seen.insert(signature.to_string(), now).is_none()
+    }
+}
+
+struct SignatureHeaders {
+    key_id: String,
+    timestamp: i64,
+    nonce: String,
+    signature: Vec<u8>,
+    signature_hex: String,
+}
+
+fn signature_headers(request: &Request) -> Option<SignatureHeaders> {
+    let header = |name: &str| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    };
+    let signature_hex = header(SIGNATURE_HEADER)?.to_ascii_lowercase();
+    Some(SignatureHeaders {
+        key_id: header(KEY_ID_HEADER)?.to_string(),
+        timestamp: header(TIMESTAMP_HEADER)?.parse().ok()?,
+        nonce: header(NONCE_HEADER).unwrap_or_default().to_string(),
+        signature: hex::decode(&signature_hex).ok()?,
+        signature_hex,
+    })
+}
+
+fn unauthorized(message: &'static str) -> Response {
+    (StatusCode::UNAUTHORIZED, message).into_response()
+}
+
+/// Authenticates requests carrying an `X-Signature` header, as an alternative to a plain
+/// `X-API-Key`. Runs before `require_api_key`, which accepts the key found here.
+pub async fn verify_signature(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let verifier = &state.signatures;
+    if !request.headers().contains_key(SIGNATURE_HEADER) {
+        if verifier.config.required {
+            return unauthorized("Signed request required");
+        }
+        return next.run(request).await;
+    }
+
+    let Some(headers) = signature_headers(&request) else {
+        return unauthorized("Invalid signature headers");
+    };
+    let now = Utc::now();
+    if !verifier.is_fresh(headers.timestamp, now) {
+        return unauthorized("Request timestamp outside the allowed window");
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_SIGNED_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => {
+            return (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response();
+        }
+    };
+    let path_and_query = parts
+        .uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    let canonical = canonical_request(
+        parts.method.as_str(),
+        path_and_query,
+        headers.timestamp,
+        &headers.nonce,
+        &body,
+    );
+
+    // Keys sharing a name are tried in turn, so a signature with either key of a rotation works
+    let key = state.api_keys.active(&headers.key_id, now).find(|key| {
+        let mut mac = new_mac(&key.key);
+        mac.update(canonical.as_bytes());
+        mac.verify_slice(&headers.signature).is_ok()
+    });
+    let Some(key) = key else {
+        return unauthorized("Invalid signature");
+    };
+    if !verifier.remember(&headers.signature_hex, now) {
+        warn!("Rejected a replayed request signed by API key {}", key.name);
+        return unauthorized("Request was already processed");
+    }
+
+    parts.extensions.insert(AuthenticatedKey {
+        name: key.name.clone(),
+        scopes: key.scopes.clone(),
+    });
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::model::recipe::flexible_date_format;
+
+/// Period recorded for wins granted before award history was tracked.
+pub const LEGACY_PERIOD: &str = "legacy";
+
+/// A single top recipe win, stored in the user's award history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopRecipeAward {
+    pub badge: String,
+    pub period: String,
+    #[serde(rename = "recipeId", default)]
+    pub recipe_id: Option<ObjectId>,
+    #[serde(rename = "awardedAt", with = "flexible_date_format")]
+    pub awarded_at: DateTime<Utc>,
+}
+
+/// Result of recording a top recipe win for an existing user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AwardOutcome {
+    /// `false` when the user had already won this badge for the same period.
+    pub newly_awarded: bool,
+    /// Total number of wins for the badge, including the one just recorded.
+    pub win_count: u32,
+}
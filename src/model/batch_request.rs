@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Body of `POST /update/batch`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BatchUpdateRequest {
+    pub user_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchItemStatus {
+    Queued,
+    /// The user id appeared earlier in the same batch, which queued it once.
+    Duplicate,
+    /// Not queued because the queue was full or the user hit its rate limit.
+    Rejected,
+}
+
+/// Outcome of one user id of a batch, in the order of the request.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct BatchItemResult {
+    pub user_id: String,
+    pub status: BatchItemStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
@@ -1,21 +1,110 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 
+use crate::config::ConfigSource;
+use crate::utils::badge::{LEVEL_BADGES, STREAK_BADGES, is_tier_badge};
+
+/// Length of the voting period a top recipe category is awarded for.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    Season,
+    Year,
+}
+
+impl Period {
+    /// Identifier of the period containing `date`, e.g. `2025-06-04` (day), `2025-W23` (week),
+    /// `2025-06` (month), `2025-summer` (season) or `2025` (year).
+    ///
+    /// Seasons are meteorological; December belongs to the winter of its own year.
+    pub fn period_of(&self, date: DateTime<Utc>) -> String {
+        match self {
+            Self::Day => date.format("%Y-%m-%d").to_string(),
+            Self::Week => {
+                let week = date.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Self::Month => format!("{}-{:02}", date.year(), date.month()),
+            Self::Season => {
+                let (year, season) = match date.month() {
+                    3..=5 => (date.year(), "spring"),
+                    6..=8 => (date.year(), "summer"),
+                    9..=11 => (date.year(), "autumn"),
+                    12 => (date.year(), "winter"),
+                    _ => (date.year() - 1, "winter"),
+                };
+                format!("{}-{}", year, season)
+            }
+            Self::Year => format!("{}", date.year()),
+        }
+    }
+
+    /// Whether `period` is an identifier [`Period::period_of`] produces for this period.
+    pub fn is_valid(&self, period: &str) -> bool {
+        self.start_of(period).is_some_and(|start| {
+            self.period_of(start.and_time(Default::default()).and_utc()) == period
+        })
+    }
+
+    /// First day of the period identified by `period`, if it is formatted like one.
+    fn start_of(&self, period: &str) -> Option<NaiveDate> {
+        match self {
+            Self::Day => NaiveDate::parse_from_str(period, "%Y-%m-%d").ok(),
+            Self::Week => {
+                let (year, week) = period.split_once("-W")?;
+                NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)
+            }
+            Self::Month => {
+                let (year, month) = period.split_once('-')?;
+                NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)
+            }
+            Self::Season => {
+                let (year, season) = period.split_once('-')?;
+                let month = match season {
+                    "spring" => 3,
+                    "summer" => 6,
+                    "autumn" => 9,
+                    "winter" => 12,
+                    _ => return None,
+                };
+                NaiveDate::from_ymd_opt(year.parse().ok()?, month, 1)
+            }
+            Self::Year => NaiveDate::from_ymd_opt(period.parse().ok()?, 1, 1),
+        }
+    }
+}
+
+/// A top recipe category defined in configuration, e.g. `best_dessert_of_the_month`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomCategory {
+    pub key: String,
+    pub badge: String,
+    pub period: Period,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Category {
     Week,
     Month,
     Year,
+    Custom(CustomCategory),
 }
 
 impl Category {
-    pub fn badge_name(&self) -> &'static str {
+    pub fn badge_name(&self) -> &str {
         match self {
             Self::Week => "recipe_of_the_week",
             Self::Month => "recipe_of_the_month",
             Self::Year => "recipe_of_the_year",
+            Self::Custom(custom) => &custom.badge,
         }
     }
 
+    /// Parses one of the built-in categories. Use [`CategoryRegistry::parse`] to
+    /// also resolve categories defined in configuration.
     pub fn parse(s: &str) -> Option<Self> {
         match s {
             "week" => Some(Self::Week),
@@ -24,11 +113,105 @@ impl Category {
             _ => None,
         }
     }
+
+    pub fn period(&self) -> Period {
+        match self {
+            Self::Week => Period::Week,
+            Self::Month => Period::Month,
+            Self::Year => Period::Year,
+            Self::Custom(custom) => custom.period,
+        }
+    }
+
+    /// Identifier of the voting period containing `date`, see [`Period::period_of`].
+    pub fn period_of(&self, date: DateTime<Utc>) -> String {
+        self.period().period_of(date)
+    }
+}
+
+/// Built-in top recipe categories plus the ones configured through
+/// `TOP_RECIPE_CATEGORIES`.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryRegistry {
+    custom: Vec<CustomCategory>,
+}
+
+impl CategoryRegistry {
+    pub fn new(custom: Vec<CustomCategory>) -> Result<Self, String> {
+        let mut keys = std::collections::HashSet::new();
+        let mut badges = std::collections::HashSet::new();
+
+        for category in &custom {
+            if category.key.is_empty() || category.badge.is_empty() {
+                return Err("Top recipe categories need a non-empty key and badge".to_string());
+            }
+            if let Some(builtin) = Category::parse(&category.key) {
+                return Err(format!(
+                    "Top recipe category {} shadows the built-in {} category",
+                    category.key,
+                    builtin.badge_name()
+                ));
+            }
+            if [Category::Week, Category::Month, Category::Year]
+                .iter()
+                .any(|builtin| builtin.badge_name() == category.badge)
+            {
+                return Err(format!(
+                    "Top recipe badge {} is already awarded by a built-in category",
+                    category.badge
+                ));
+            }
+            if is_tier_badge(&category.badge)
+                || LEVEL_BADGES
+                    .iter()
+                    .any(|(_, badge)| *badge == category.badge)
+                || STREAK_BADGES.contains(&category.badge.as_str())
+            {
+                return Err(format!(
+                    "Top recipe badge {} collides with a level, streak or tier badge",
+                    category.badge
+                ));
+            }
+            if !keys.insert(category.key.as_str()) {
+                return Err(format!("Duplicate top recipe category: {}", category.key));
+            }
+            if !badges.insert(category.badge.as_str()) {
+                return Err(format!("Duplicate top recipe badge: {}", category.badge));
+            }
+        }
+
+        Ok(Self { custom })
+    }
+
+    /// Reads custom categories from the `TOP_RECIPE_CATEGORIES` setting, a JSON array such as
+    /// `[{"key": "day", "badge": "recipe_of_the_day", "period": "day"}]`, or an array of
+    /// tables in the config file.
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        match source.var("TOP_RECIPE_CATEGORIES") {
+            Some(raw) if !raw.trim().is_empty() => {
+                let custom = serde_json::from_str(&raw)
+                    .map_err(|e| format!("Invalid TOP_RECIPE_CATEGORIES: {}", e))?;
+                Self::new(custom)
+            }
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn parse(&self, s: &str) -> Option<Category> {
+        Category::parse(s).or_else(|| {
+            self.custom
+                .iter()
+                .find(|category| category.key == s)
+                .cloned()
+                .map(Category::Custom)
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn test_category_badge_name() {
@@ -45,4 +228,134 @@ mod tests {
         assert_eq!(Category::parse("invalid"), None);
         assert_eq!(Category::parse(""), None);
     }
+
+    #[test]
+    fn test_category_period_of() {
+        let date = Utc.with_ymd_and_hms(2025, 6, 4, 12, 0, 0).unwrap();
+        assert_eq!(Category::Week.period_of(date), "2025-W23");
+        assert_eq!(Category::Month.period_of(date), "2025-06");
+        assert_eq!(Category::Year.period_of(date), "2025");
+
+        // ISO weeks can belong to the previous year
+        let new_year = Utc.with_ymd_and_hms(2027, 1, 1, 12, 0, 0).unwrap();
+        assert_eq!(Category::Week.period_of(new_year), "2026-W53");
+    }
+
+    #[test]
+    fn test_period_of_day_and_season() {
+        let date = Utc.with_ymd_and_hms(2025, 6, 4, 12, 0, 0).unwrap();
+        assert_eq!(Period::Day.period_of(date), "2025-06-04");
+        assert_eq!(Period::Season.period_of(date), "2025-summer");
+
+        let december = Utc.with_ymd_and_hms(2025, 12, 20, 12, 0, 0).unwrap();
+        let february = Utc.with_ymd_and_hms(2026, 2, 10, 12, 0, 0).unwrap();
+        assert_eq!(Period::Season.period_of(december), "2025-winter");
+        assert_eq!(Period::Season.period_of(february), "2025-winter");
+    }
+
+    #[test]
+    fn test_period_is_valid() {
+        assert!(Period::Day.is_valid("2025-06-04"));
+        assert!(Period::Week.is_valid("2026-W53"));
+        assert!(Period::Month.is_valid("2025-06"));
+        assert!(Period::Season.is_valid("2025-winter"));
+        assert!(Period::Year.is_valid("2025"));
+
+        assert!(!Period::Day.is_valid("2025-6-4"));
+        assert!(!Period::Day.is_valid("2025-02-30"));
+        assert!(!Period::Week.is_valid("2025-W99"));
+        assert!(!Period::Week.is_valid("2025-W53"));
+        assert!(!Period::Week.is_valid("2025-W5"));
+        assert!(!Period::Month.is_valid("2025-13"));
+        assert!(!Period::Month.is_valid("2025-W23"));
+        assert!(!Period::Season.is_valid("2025-fall"));
+        assert!(!Period::Year.is_valid("x"));
+        assert!(!Period::Year.is_valid("+2025"));
+        assert!(!Period::Week.is_valid(""));
+    }
+
+    fn custom(key: &str, badge: &str, period: Period) -> CustomCategory {
+        CustomCategory {
+            key: key.to_string(),
+            badge: badge.to_string(),
+            period,
+        }
+    }
+
+    #[test]
+    fn test_registry_parse() {
+        let registry = CategoryRegistry::new(vec![
+            custom("day", "recipe_of_the_day", Period::Day),
+            custom(
+                "best_dessert_of_the_month",
+                "best_dessert_of_the_month",
+                Period::Month,
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(registry.parse("week"), Some(Category::Week));
+        assert_eq!(registry.parse("year"), Some(Category::Year));
+
+        let day = registry.parse("day").unwrap();
+        assert_eq!(day.badge_name(), "recipe_of_the_day");
+        assert_eq!(day.period(), Period::Day);
+
+        let dessert = registry.parse("best_dessert_of_the_month").unwrap();
+        assert_eq!(dessert.badge_name(), "best_dessert_of_the_month");
+        assert_eq!(dessert.period(), Period::Month);
+
+        assert_eq!(registry.parse("season"), None);
+        assert_eq!(CategoryRegistry::default().parse("day"), None);
+    }
+
+    #[test]
+    fn test_registry_rejects_invalid_categories() {
+        assert!(CategoryRegistry::new(vec![custom("week", "weekly", Period::Week)]).is_err());
+        assert!(CategoryRegistry::new(vec![custom("", "badge", Period::Day)]).is_err());
+        assert!(
+            CategoryRegistry::new(vec![
+                custom("day", "recipe_of_the_day", Period::Day),
+                custom("day", "other", Period::Day),
+            ])
+            .is_err()
+        );
+        assert!(
+            CategoryRegistry::new(vec![
+                custom("day", "recipe_of_the_day", Period::Day),
+                custom("today", "recipe_of_the_day", Period::Day),
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_registry_rejects_colliding_badges() {
+        for badge in [
+            "recipe_of_the_week",
+            "recipe_of_the_month_5x",
+            "recipe_of_the_day_10x",
+            "level_100",
+            "week_streak",
+        ] {
+            assert!(
+                CategoryRegistry::new(vec![custom("day", badge, Period::Day)]).is_err(),
+                "{} was accepted",
+                badge
+            );
+        }
+        assert!(CategoryRegistry::new(vec![custom("day", "level_1000", Period::Day)]).is_ok());
+    }
+
+    #[test]
+    fn test_custom_category_deserialize() {
+        let categories: Vec<CustomCategory> = serde_json::from_str(
+            r#"[{"key": "season", "badge": "recipe_of_the_season", "period": "season"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            categories,
+            vec![custom("season", "recipe_of_the_season", Period::Season)]
+        );
+    }
 }
@@ -1,5 +1,10 @@
+pub mod award;
+pub mod batch_request;
 pub mod category;
 pub mod level;
 pub mod recipe;
+pub mod review;
+pub mod stats;
 pub mod top_recipe_request;
 pub mod user;
+pub mod verification_request;
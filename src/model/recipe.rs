@@ -14,7 +14,7 @@ pub struct Recipe {
 }
 
 // Custom serialization/deserialization for flexible date handling
-mod flexible_date_format {
+pub(crate) mod flexible_date_format {
     use super::*;
 
     pub fn serialize<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
@@ -109,6 +109,31 @@ mod flexible_date_format {
     }
 }
 
+// Same as `flexible_date_format` for optional date fields
+pub(crate) mod optional_flexible_date_format {
+    use super::*;
+
+    pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match date {
+            Some(date) => flexible_date_format::serialize(date, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "flexible_date_format")] DateTime<Utc>);
+
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|wrapper| wrapper.0))
+    }
+}
+
 impl Serialize for Recipe {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::model::recipe::{flexible_date_format, optional_flexible_date_format};
+
+/// A suspicious activity pattern detected by the abuse detector.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "signal", rename_all = "snake_case")]
+pub enum AbuseSignal {
+    /// Many recipes were created within a short window.
+    RecipeBurst { recipes: u32, window_secs: u32 },
+    /// Most likes were received by recipes created very recently.
+    LikesOnNewRecipes { likes: u32, share_percent: u32 },
+    /// The level jumped sharply since the previous recomputation.
+    LikeSpike { level_delta: u32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// Badge awards withheld from a flagged user until an admin reviews them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BadgeReview {
+    pub _id: ObjectId,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    pub badges: Vec<String>,
+    /// Whether automatic verification was withheld as well.
+    #[serde(default)]
+    pub verify: bool,
+    pub signals: Vec<AbuseSignal>,
+    pub score: u32,
+    pub status: ReviewStatus,
+    #[serde(rename = "createdAt", with = "flexible_date_format")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "resolvedAt", default, with = "optional_flexible_date_format")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// Name of the API key that approved or rejected the review.
+    #[serde(rename = "resolvedBy", default)]
+    pub resolved_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewAction {
+    Approve,
+    Reject,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewDecisionRequest {
+    pub action: ReviewAction,
+}
@@ -0,0 +1,36 @@
+use chrono::Datelike;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::model::recipe::Recipe;
+
+/// Aggregated recipe activity of a single user.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct UserStats {
+    pub num_recipes: u32,
+    pub total_likes: u32,
+    /// Distinct calendar days (UTC) on which the user published a recipe.
+    pub active_days: u32,
+    /// Distinct ISO weeks in which the user published a recipe.
+    pub active_weeks: u32,
+}
+
+impl UserStats {
+    pub fn from_recipes(recipes: &[Recipe]) -> Self {
+        let days: HashSet<_> = recipes.iter().map(|r| r.created_at.date_naive()).collect();
+        let weeks: HashSet<_> = recipes
+            .iter()
+            .map(|r| {
+                let week = r.created_at.date_naive().iso_week();
+                (week.year(), week.week())
+            })
+            .collect();
+
+        Self {
+            num_recipes: recipes.len() as u32,
+            total_likes: recipes.iter().map(|r| r.num_likes.max(0) as u32).sum(),
+            active_days: days.len() as u32,
+            active_weeks: weeks.len() as u32,
+        }
+    }
+}
@@ -5,4 +5,7 @@ pub struct TopRecipeRequest {
     pub category: String,
     pub user_id: String,
     pub recipe_id: Option<String>,
+    /// Voting period the win belongs to (e.g. `2025-W23`). Defaults to the current period.
+    #[serde(default)]
+    pub period: Option<String>,
 }
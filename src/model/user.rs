@@ -1,6 +1,10 @@
+use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 
+use crate::model::award::TopRecipeAward;
+use crate::model::recipe::optional_flexible_date_format;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct User {
     pub _id: ObjectId,
@@ -12,6 +16,18 @@ pub struct User {
     pub badges: Vec<String>,
     #[serde(default)]
     pub verified: Option<bool>,
+    #[serde(rename = "topRecipeAwards", default)]
+    pub top_recipe_awards: Vec<TopRecipeAward>,
+    #[serde(rename = "createdAt", default, with = "optional_flexible_date_format")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Set by an admin override; the processor never changes `verified` while locked.
+    #[serde(rename = "verificationLocked", default)]
+    pub verification_locked: Option<bool>,
+    #[serde(rename = "verificationReason", default)]
+    pub verification_reason: Option<String>,
+    /// Name of the API key that last set the verified status through an admin endpoint.
+    #[serde(rename = "verificationUpdatedBy", default)]
+    pub verification_updated_by: Option<String>,
 }
 
 impl User {
@@ -20,4 +36,17 @@ impl User {
             self.badges = Vec::new();
         }
     }
+
+    /// Number of recorded wins of the given top recipe badge.
+    pub fn top_recipe_wins(&self, badge: &str) -> u32 {
+        self.top_recipe_awards
+            .iter()
+            .filter(|award| award.badge == badge)
+            .count() as u32
+    }
+
+    /// Whether the user holds a top recipe badge granted before win history was recorded.
+    pub fn has_unrecorded_win(&self, badge: &str) -> bool {
+        self.badges.iter().any(|b| b == badge) && self.top_recipe_wins(badge) == 0
+    }
 }
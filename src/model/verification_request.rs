@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VerificationOverrideRequest {
+    pub user_id: String,
+    pub verified: bool,
+    pub reason: String,
+    /// Prevents the processor from changing `verified` automatically. Defaults to `true`.
+    #[serde(default = "default_locked")]
+    pub locked: bool,
+}
+
+fn default_locked() -> bool {
+    true
+}
@@ -1,20 +1,35 @@
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{Mutex, mpsc};
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::error::Error;
 use crate::model::level::LevelRequest;
+use crate::service::tracking::RequestTracker;
+
+/// How long clients are asked to wait when the queue is full.
+pub const QUEUE_FULL_RETRY_AFTER: Duration = Duration::from_secs(5);
 
 /// Queue trait defining operations for a badge update queue
 #[async_trait]
 pub trait BadgeUpdateQueue: Send + Sync {
-    async fn enqueue(&self, request: LevelRequest) -> Result<(), String>;
+    /// Enqueues a request, waiting for room when the queue is full.
+    async fn enqueue(&self, request: LevelRequest) -> Result<(), Error>;
+    /// Enqueues a request without waiting, failing with `Error::TooManyRequests` when the
+    /// queue is full. Used by HTTP handlers, which should not hang on a saturated queue.
+    async fn try_enqueue(&self, request: LevelRequest) -> Result<(), Error>;
     async fn get_pending_requests(&self) -> Vec<LevelRequest>;
+    /// Maximum number of pending requests, if the queue is bounded.
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
 }
 
 pub struct InMemoryQueue {
     sender: mpsc::Sender<LevelRequest>,
     pending_requests: Arc<Mutex<Vec<LevelRequest>>>,
+    tracker: RequestTracker,
 }
 
 impl InMemoryQueue {
@@ -25,22 +40,20 @@ impl InMemoryQueue {
         let queue = Self {
             sender,
             pending_requests,
+            tracker: RequestTracker::default(),
         };
 
         (queue, receiver)
     }
 
-    pub async fn remove_request(&self, request_id: &str) {
-        let mut pending = self.pending_requests.lock().await;
-        if let Some(pos) = pending.iter().position(|req| req.request_id == request_id) {
-            pending.remove(pos);
-        }
+    /// Records every enqueued request as queued in `tracker`.
+    pub fn with_tracker(mut self, tracker: RequestTracker) -> Self {
+        self.tracker = tracker;
+        self
     }
-}
 
-#[async_trait]
-impl BadgeUpdateQueue for InMemoryQueue {
-    async fn enqueue(&self, mut request: LevelRequest) -> Result<(), String> {
+    /// Fills in the request id and creation time when the client left them empty.
+    fn prepare(mut request: LevelRequest) -> LevelRequest {
         if request.request_id.is_empty() {
             request.request_id = uuid::Uuid::new_v4().to_string();
         }
@@ -48,25 +61,72 @@ impl BadgeUpdateQueue for InMemoryQueue {
         if request.created_at.timestamp() == 0 {
             request.created_at = chrono::Utc::now();
         }
+        request
+    }
+
+    /// Registers the request as pending, then sends it through the reserved slot. The
+    /// processor removes requests once it receives them, so they must be pending first.
+    async fn send(&self, permit: mpsc::Permit<'_, LevelRequest>, request: LevelRequest) {
+        let mut pending = self.pending_requests.lock().await;
+        pending.push(request.clone());
+        info!("Queue size: {} requests pending", pending.len());
+        drop(pending);
+
+        self.tracker.queued(&request);
+        permit.send(request);
+    }
 
-        // First send to the channel
-        self.sender
-            .send(request.clone())
-            .await
-            .map_err(|e| format!("Failed to enqueue badge update request: {}", e))?;
-
-        // Only after successful send, add to pending_requests
-        {
-            let mut pending = self.pending_requests.lock().await;
-            pending.push(request);
-            info!("Queue size: {} requests pending", pending.len());
+    pub async fn remove_request(&self, request_id: &str) {
+        let mut pending = self.pending_requests.lock().await;
+        if let Some(pos) = pending.iter().position(|req| req.request_id == request_id) {
+            pending.remove(pos);
         }
+    }
+}
 
+#[async_trait]
+impl BadgeUpdateQueue for InMemoryQueue {
+    async fn enqueue(&self, request: LevelRequest) -> Result<(), Error> {
+        let request = Self::prepare(request);
+
+        // Waiting for a slot first leaves nothing behind if this call is cancelled
+        let permit = self.sender.reserve().await.map_err(|e| {
+            Error::Permanent(format!("Failed to enqueue badge update request: {}", e))
+        })?;
+        self.send(permit, request).await;
         Ok(())
     }
 
+    async fn try_enqueue(&self, request: LevelRequest) -> Result<(), Error> {
+        let request = Self::prepare(request);
+
+        match self.sender.try_reserve() {
+            Ok(permit) => {
+                self.send(permit, request).await;
+                Ok(())
+            }
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                warn!(
+                    "Badge update queue is full, rejecting request for user {}",
+                    request.user_id
+                );
+                Err(Error::TooManyRequests {
+                    message: "Badge update queue is full, please retry later".to_string(),
+                    retry_after: QUEUE_FULL_RETRY_AFTER,
+                })
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => Err(Error::Permanent(
+                "Failed to enqueue badge update request: channel closed".to_string(),
+            )),
+        }
+    }
+
     async fn get_pending_requests(&self) -> Vec<LevelRequest> {
         let pending = self.pending_requests.lock().await;
         pending.clone()
     }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.sender.max_capacity())
+    }
 }
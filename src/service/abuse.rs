@@ -0,0 +1,147 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::config::ConfigSource;
+use crate::model::recipe::Recipe;
+use crate::model::review::AbuseSignal;
+
+/// Scores recipe activity for like-farming patterns. Each detected signal adds one
+/// point to the score and users reaching `flag_score` are flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AbuseDetector {
+    pub enabled: bool,
+    pub burst_window_secs: u32,
+    pub burst_min_recipes: u32,
+    pub new_recipe_max_age_hours: u32,
+    pub new_recipe_likes_share_percent: u32,
+    pub new_recipe_min_likes: u32,
+    pub spike_min_level_delta: u32,
+    pub flag_score: u32,
+}
+
+impl Default for AbuseDetector {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            burst_window_secs: 60,
+            burst_min_recipes: 5,
+            new_recipe_max_age_hours: 24,
+            new_recipe_likes_share_percent: 80,
+            new_recipe_min_likes: 50,
+            spike_min_level_delta: 200,
+            flag_score: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AbuseReport {
+    pub score: u32,
+    pub flagged: bool,
+    pub signals: Vec<AbuseSignal>,
+}
+
+impl AbuseDetector {
+    /// Reads the detector settings from the `ABUSE_*` settings, falling back
+    /// to the defaults for unset ones. Detection is disabled unless
+    /// `ABUSE_DETECTION_ENABLED` is `true`.
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let defaults = Self::default();
+        Ok(Self {
+            enabled: source.bool("ABUSE_DETECTION_ENABLED", defaults.enabled)?,
+            burst_window_secs: source.u32("ABUSE_BURST_WINDOW_SECS", defaults.burst_window_secs)?,
+            burst_min_recipes: source.u32("ABUSE_BURST_MIN_RECIPES", defaults.burst_min_recipes)?,
+            new_recipe_max_age_hours: source.u32(
+                "ABUSE_NEW_RECIPE_MAX_AGE_HOURS",
+                defaults.new_recipe_max_age_hours,
+            )?,
+            new_recipe_likes_share_percent: source.u32(
+                "ABUSE_NEW_RECIPE_LIKES_SHARE_PERCENT",
+                defaults.new_recipe_likes_share_percent,
+            )?,
+            new_recipe_min_likes: source
+                .u32("ABUSE_NEW_RECIPE_MIN_LIKES", defaults.new_recipe_min_likes)?,
+            spike_min_level_delta: source.u32(
+                "ABUSE_SPIKE_MIN_LEVEL_DELTA",
+                defaults.spike_min_level_delta,
+            )?,
+            flag_score: source.u32("ABUSE_FLAG_SCORE", defaults.flag_score)?,
+        })
+    }
+
+    /// Scores a user's recipes. `previous_level` is the level stored by the last
+    /// recomputation; users that were never computed (level 0) are not checked for spikes.
+    pub fn evaluate(
+        &self,
+        recipes: &[Recipe],
+        previous_level: i32,
+        new_level: i32,
+        now: DateTime<Utc>,
+    ) -> AbuseReport {
+        if !self.enabled {
+            return AbuseReport {
+                score: 0,
+                flagged: false,
+                signals: Vec::new(),
+            };
+        }
+
+        let mut signals = Vec::new();
+
+        let burst = max_recipes_in_window(recipes, self.burst_window_secs);
+        if self.burst_min_recipes > 0 && burst >= self.burst_min_recipes {
+            signals.push(AbuseSignal::RecipeBurst {
+                recipes: burst,
+                window_secs: self.burst_window_secs,
+            });
+        }
+
+        let total_likes: u32 = recipes.iter().map(|r| r.num_likes.max(0) as u32).sum();
+        let new_since = now - Duration::hours(self.new_recipe_max_age_hours as i64);
+        let new_recipe_likes: u32 = recipes
+            .iter()
+            .filter(|r| r.created_at >= new_since)
+            .map(|r| r.num_likes.max(0) as u32)
+            .sum();
+        if total_likes > 0 && new_recipe_likes >= self.new_recipe_min_likes {
+            let share_percent = (new_recipe_likes as u64 * 100 / total_likes as u64) as u32;
+            if share_percent >= self.new_recipe_likes_share_percent {
+                signals.push(AbuseSignal::LikesOnNewRecipes {
+                    likes: new_recipe_likes,
+                    share_percent,
+                });
+            }
+        }
+
+        if previous_level > 0 {
+            let level_delta = (new_level - previous_level).max(0) as u32;
+            if level_delta >= self.spike_min_level_delta {
+                signals.push(AbuseSignal::LikeSpike { level_delta });
+            }
+        }
+
+        let score = signals.len() as u32;
+        AbuseReport {
+            score,
+            flagged: score > 0 && score >= self.flag_score,
+            signals,
+        }
+    }
+}
+
+// Largest number of recipes created within any window of `window_secs` seconds
+fn max_recipes_in_window(recipes: &[Recipe], window_secs: u32) -> u32 {
+    let mut timestamps: Vec<DateTime<Utc>> = recipes.iter().map(|r| r.created_at).collect();
+    timestamps.sort();
+
+    let window = Duration::seconds(window_secs as i64);
+    let mut max = 0;
+    let mut start = 0;
+    for end in 0..timestamps.len() {
+        while timestamps[end] - timestamps[start] > window {
+            start += 1;
+        }
+        max = max.max(end - start + 1);
+    }
+    max as u32
+}
@@ -1,117 +1,458 @@
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
+use std::time::Duration;
 
+use futures_util::FutureExt;
 use mongodb::bson::oid::ObjectId;
-use tokio::sync::mpsc;
-use tracing::{error, info};
+use tokio::sync::{Mutex, mpsc};
+use tracing::{error, info, warn};
 
 use crate::{
-    model::level::LevelRequest,
+    error::Error,
+    model::{
+        level::LevelRequest,
+        review::{BadgeReview, ReviewStatus},
+    },
     queue::InMemoryQueue,
-    service::{db::Database, notifier::Notifier},
-    utils::{badge::assign_badges, level::calculate_level},
+    service::{
+        abuse::AbuseDetector,
+        connectivity::Connectivity,
+        db::{BadgeUpdate, Database},
+        heartbeat::Heartbeat,
+        metrics::ProcessorMetrics,
+        notifier::Notifier,
+        tracking::{RequestOutcome, RequestTracker},
+        verification::VerificationPolicy,
+    },
+    utils::{
+        badge::{assign_badges, needs_recipe_history},
+        level::calculate_level,
+    },
 };
 
+/// How often a badge update is recomputed when the user changes concurrently.
+const MAX_UPDATE_ATTEMPTS: u32 = 5;
+/// How often a request is retried after a transient failure before it is dropped.
+const MAX_TRANSIENT_RETRIES: u32 = 3;
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// How often the processor beats while it waits for requests.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// Pause before restarting a processing task that died, so a persistent bug cannot spin.
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
 pub struct BadgeForgeProcessor {
     db: Arc<dyn Database>,
     notifier: Arc<dyn Notifier>,
+    verification_policy: VerificationPolicy,
+    abuse_detector: AbuseDetector,
+    connectivity: Connectivity,
+    heartbeat: Heartbeat,
+    metrics: Arc<ProcessorMetrics>,
+    tracker: RequestTracker,
+    worker_count: usize,
 }
 
 impl BadgeForgeProcessor {
     pub fn new(db: Arc<dyn Database>, notifier: Arc<dyn Notifier>) -> Self {
-        Self { db, notifier }
+        Self {
+            db,
+            notifier,
+            verification_policy: VerificationPolicy::default(),
+            abuse_detector: AbuseDetector::default(),
+            connectivity: Connectivity::default(),
+            heartbeat: Heartbeat::default(),
+            metrics: Arc::new(ProcessorMetrics::default()),
+            tracker: RequestTracker::default(),
+            worker_count: 1,
+        }
+    }
+
+    pub fn with_verification_policy(mut self, policy: VerificationPolicy) -> Self {
+        self.verification_policy = policy;
+        self
+    }
+
+    pub fn with_abuse_detector(mut self, detector: AbuseDetector) -> Self {
+        self.abuse_detector = detector;
+        self
     }
 
-    pub async fn start(
-        self,
-        mut receiver: mpsc::Receiver<LevelRequest>,
+    /// Pauses consumption of the queue while the database is unreachable.
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
+    /// Beats at least every `HEARTBEAT_INTERVAL` while the processing loop runs and is not
+    /// busy with a request.
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<ProcessorMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Records in `tracker` when requests are processed, retried, completed or failed.
+    pub fn with_tracker(mut self, tracker: RequestTracker) -> Self {
+        self.tracker = tracker;
+        self
+    }
+
+    /// Number of requests processed concurrently. Concurrent updates of the same user are
+    /// safe, since badge updates are compare-and-swap writes.
+    pub fn with_worker_count(mut self, worker_count: usize) -> Self {
+        self.worker_count = worker_count.max(1);
+        self
+    }
+
+    /// Spawns the processing tasks, each under a supervisor that restarts it if it dies. A
+    /// panic while processing a request only drops that request.
+    pub async fn start(self, receiver: mpsc::Receiver<LevelRequest>, queue: Arc<InMemoryQueue>) {
+        let processor = Arc::new(self);
+        // Shared by the workers, and so a restarted task can take over from the one that died
+        let receiver = Arc::new(Mutex::new(receiver));
+        for worker in 0..processor.worker_count {
+            tokio::spawn(
+                processor
+                    .clone()
+                    .supervise(worker, receiver.clone(), queue.clone()),
+            );
+        }
+    }
+
+    async fn supervise(
+        self: Arc<Self>,
+        worker: usize,
+        receiver: Arc<Mutex<mpsc::Receiver<LevelRequest>>>,
         queue: Arc<InMemoryQueue>,
     ) {
-        tokio::spawn(async move {
-            info!("Badge Forge Processor started");
-            while let Some(request) = receiver.recv().await {
-                let request_id = request.request_id.clone();
-                if let Err(e) = self.process_request(request).await {
+        loop {
+            let task = tokio::spawn(self.clone().run(worker, receiver.clone(), queue.clone()));
+            match task.await {
+                Ok(()) => {
+                    info!(
+                        "Badge Forge Processor worker {} stopped, the queue was closed",
+                        worker
+                    );
+                    return;
+                }
+                Err(e) if e.is_panic() => {
+                    error!(
+                        "Badge Forge Processor worker {} died, restarting in {:?}: {}",
+                        worker,
+                        RESTART_DELAY,
+                        panic_message(e.into_panic().as_ref())
+                    );
+                    self.metrics.record_restart();
+                    tokio::time::sleep(RESTART_DELAY).await;
+                }
+                Err(e) => {
+                    error!(
+                        "Badge Forge Processor worker {} was cancelled: {}",
+                        worker, e
+                    );
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        worker: usize,
+        receiver: Arc<Mutex<mpsc::Receiver<LevelRequest>>>,
+        queue: Arc<InMemoryQueue>,
+    ) {
+        info!("Badge Forge Processor worker {} started", worker);
+        loop {
+            self.heartbeat.beat();
+            let request = tokio::select! {
+                request = self.next_request(&receiver) => request,
+                _ = tokio::time::sleep(HEARTBEAT_INTERVAL) => continue,
+            };
+            let Some(request) = request else {
+                break;
+            };
+            let outcome = AssertUnwindSafe(self.process_with_retries(request.clone()))
+                .catch_unwind()
+                .await;
+            if let Err(panic) = outcome {
+                self.metrics.record_panic();
+                error!(
+                    "Badge update for user {} panicked: {}",
+                    request.user_id,
+                    panic_message(panic.as_ref())
+                );
+                self.tracker
+                    .failed(&request, "Processing panicked".to_string());
+            }
+            queue.remove_request(&request.request_id).await;
+        }
+    }
+
+    /// Waits for the next request. Requests stay queued while the database is down.
+    async fn next_request(
+        &self,
+        receiver: &Mutex<mpsc::Receiver<LevelRequest>>,
+    ) -> Option<LevelRequest> {
+        self.connectivity.wait_until_ready().await;
+        // Held only while waiting, so the other workers take the next requests
+        receiver.lock().await.recv().await
+    }
+
+    /// Processes a request, retrying with exponential backoff while the failure is retryable.
+    /// While the database is known to be down the request waits for it instead, without
+    /// using up its retries.
+    async fn process_with_retries(&self, request: LevelRequest) {
+        let mut retry_delay = INITIAL_RETRY_DELAY;
+        let mut retry = 0;
+        loop {
+            self.tracker.processing(&request);
+            match self.process_request(request.clone()).await {
+                Ok(outcome) => {
+                    self.metrics.record_processed();
+                    self.tracker.completed(&request, outcome);
+                    return;
+                }
+                Err(e) if e.is_retryable() && !self.connectivity.is_ready() => {
+                    warn!(
+                        "Badge update for user {} failed while the database is down, waiting: {}",
+                        request.user_id, e
+                    );
+                    self.tracker.retrying(
+                        &request,
+                        format!("Waiting for the database: {}", e.public_message()),
+                    );
+                    self.connectivity.wait_until_ready().await;
+                }
+                Err(e) if e.is_retryable() && retry < MAX_TRANSIENT_RETRIES => {
+                    warn!(
+                        "Badge update for user {} failed, retrying in {:?}: {}",
+                        request.user_id, retry_delay, e
+                    );
+                    self.tracker.retrying(&request, e.public_message());
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay *= 2;
+                    retry += 1;
+                }
+                Err(e) => {
                     error!("Error processing badge update request: {}", e);
+                    self.metrics.record_failed();
+                    self.tracker.failed(&request, e.public_message());
+                    return;
                 }
-                queue.remove_request(&request_id).await;
             }
-        });
+        }
     }
 
-    async fn process_request(&self, request: LevelRequest) -> Result<(), String> {
+    /// Recomputes the level, badges and verification of the user, returning what changed.
+    pub async fn process_request(&self, request: LevelRequest) -> Result<RequestOutcome, Error> {
         info!("Processing badge update for user: {}", request.user_id);
 
         let user_id = match ObjectId::parse_str(&request.user_id) {
             Ok(id) => id,
-            Err(_) => return Err(format!("Invalid user ID format: {}", request.user_id)),
+            Err(_) => {
+                return Err(Error::InvalidId(format!(
+                    "Invalid user ID format: {}",
+                    request.user_id
+                )));
+            }
         };
 
+        for attempt in 1..=MAX_UPDATE_ATTEMPTS {
+            if let Some(applied) = self.try_update_user(&user_id, &request).await? {
+                self.notify(&request, &applied).await;
+
+                info!(
+                    "Updated level and badges for user {}: level {}, new badges {:?}, verified {}",
+                    request.user_id, applied.level, applied.new_badges, applied.verified
+                );
+                return Ok(RequestOutcome {
+                    previous_level: applied.previous_level,
+                    level: applied.level,
+                    badges_added: applied.new_badges,
+                    verified: applied.verified,
+                    newly_verified: applied.newly_verified,
+                    held_for_review: applied.held_for_review,
+                });
+            }
+
+            warn!(
+                "User {} changed while computing badges, retrying ({}/{})",
+                request.user_id, attempt, MAX_UPDATE_ATTEMPTS
+            );
+        }
+
+        Err(Error::Conflict(format!(
+            "Failed to update user {} after {} conflicting attempts",
+            request.user_id, MAX_UPDATE_ATTEMPTS
+        )))
+    }
+
+    /// Computes and writes the user's level and badges from a fresh snapshot.
+    /// Returns `None` if the user changed concurrently and the write was rejected.
+    async fn try_update_user(
+        &self,
+        user_id: &ObjectId,
+        request: &LevelRequest,
+    ) -> Result<Option<AppliedUpdate>, Error> {
         let mut user = self
             .db
-            .find_user(&user_id)
+            .find_user(user_id)
             .await?
-            .ok_or_else(|| format!("User not found: {}", request.user_id))?;
+            .ok_or_else(|| Error::NotFound(format!("User not found: {}", request.user_id)))?;
         user.ensure_badges();
 
-        let user_recipes = self.db.get_user_recipes(&user_id).await?;
+        // Cached aggregates loaded before the request may miss the likes it was sent for
+        self.db.expire_aggregates(user_id, request.created_at);
+        let stats = self.db.get_user_stats(user_id).await?;
 
-        let num_recipes = user_recipes.len();
-        let user_num_likes: u32 = user_recipes.iter().map(|r| r.num_likes as u32).sum();
+        // Full recipe lists are only needed for streak badges and abuse detection
+        let user_recipes = if self.abuse_detector.enabled || needs_recipe_history(&user.badges) {
+            self.db.get_user_recipes(user_id).await?
+        } else {
+            Vec::new()
+        };
 
-        let new_user_level = calculate_level(num_recipes as u32, user_num_likes) as i32;
+        let new_user_level = calculate_level(stats.num_recipes, stats.total_likes) as i32;
+
+        let abuse_report = self.abuse_detector.evaluate(
+            &user_recipes,
+            user.level,
+            new_user_level,
+            chrono::Utc::now(),
+        );
 
         let mut updated_badges = user.badges.clone();
         assign_badges(&mut updated_badges, new_user_level, user_recipes);
+        let mut new_badges: Vec<String> = updated_badges
+            .into_iter()
+            .filter(|b| !user.badges.contains(b))
+            .collect();
 
         let is_already_verified = user.verified.unwrap_or(false);
-        let verified = if num_recipes >= 30 {
-            true
-        } else {
-            is_already_verified
-        };
+        let verification_locked = user.verification_locked.unwrap_or(false);
+        let eligible = is_already_verified
+            || (!verification_locked
+                && self
+                    .verification_policy
+                    .evaluate(&stats, user.created_at, chrono::Utc::now())
+                    .eligible);
 
-        let newly_verified = verified && !is_already_verified;
+        let mut newly_verified = eligible && !is_already_verified;
+        let mut held_for_review = false;
 
-        self.db
-            .update_user_badges_and_level(&user_id, &updated_badges, new_user_level, verified)
-            .await?;
-
-        let old_badges: std::collections::HashSet<_> = user.badges.iter().collect();
-        let new_badges: Vec<_> = updated_badges
+        // What an admin still has to review, or refused, is not granted by a later update
+        let reviews = self.db.get_user_badge_reviews(user_id).await?;
+        for review in reviews
             .iter()
-            .filter(|b| !old_badges.contains(b))
-            .collect();
-
-        if !new_badges.is_empty()
-            && let Some(ref email) = user.email
+            .filter(|review| review.status != ReviewStatus::Approved)
         {
-            for badge in new_badges {
-                let metadata = serde_json::json!({
-                    "badgeName": badge,
-                    "userId": &request.user_id
-                });
+            let pending = review.status == ReviewStatus::Pending;
+            let before = new_badges.len();
+            new_badges.retain(|badge| !review.badges.contains(badge));
+            held_for_review |= pending && new_badges.len() < before;
+            if review.verify && newly_verified {
+                newly_verified = false;
+                held_for_review |= pending;
+            }
+        }
 
-                self.notifier
-                    .send_notification("NEW_BADGE", email, metadata)
-                    .await;
+        // Flagged users keep their level, but new badges and verification wait for an admin
+        if abuse_report.flagged {
+            if !new_badges.is_empty() || newly_verified {
+                warn!(
+                    "Holding badges {:?} (verify: {}) of user {} for review: {:?}",
+                    new_badges, newly_verified, request.user_id, abuse_report.signals
+                );
+                let review = BadgeReview {
+                    _id: ObjectId::new(),
+                    user_id: *user_id,
+                    badges: new_badges,
+                    verify: newly_verified,
+                    signals: abuse_report.signals.clone(),
+                    score: abuse_report.score,
+                    status: ReviewStatus::Pending,
+                    created_at: chrono::Utc::now(),
+                    resolved_at: None,
+                    resolved_by: None,
+                };
+                self.db.hold_badges_for_review(&review).await?;
+                held_for_review = true;
             }
+
+            new_badges = Vec::new();
+            newly_verified = false;
         }
 
-        if newly_verified && let Some(ref email) = user.email {
+        let update = BadgeUpdate {
+            expected_level: user.level,
+            level: new_user_level,
+            add_badges: new_badges,
+            verified: newly_verified.then_some(true),
+        };
+        if !self.db.apply_badge_update(user_id, &update).await? {
+            return Ok(None);
+        }
+
+        Ok(Some(AppliedUpdate {
+            email: user.email,
+            previous_level: user.level,
+            level: new_user_level,
+            new_badges: update.add_badges,
+            newly_verified,
+            verified: is_already_verified || newly_verified,
+            held_for_review,
+        }))
+    }
+
+    async fn notify(&self, request: &LevelRequest, applied: &AppliedUpdate) {
+        let Some(ref email) = applied.email else {
+            return;
+        };
+
+        for badge in &applied.new_badges {
             let metadata = serde_json::json!({
+                "badgeName": badge,
                 "userId": &request.user_id
             });
 
             self.notifier
-                .send_notification("VERIFIED", email, metadata)
+                .send_notification("NEW_BADGE", email, metadata)
                 .await;
         }
 
-        info!(
-            "Updated level and badges for user {}: level {}, badges {:?}, verified {}",
-            request.user_id, new_user_level, updated_badges, verified
-        );
+        if applied.newly_verified {
+            let metadata = serde_json::json!({
+                "userId": &request.user_id
+            });
 
-        Ok(())
+            self.notifier
+                .send_notification("VERIFIED", email, metadata)
+                .await;
+        }
     }
 }
+
+/// Changes written by a successful update attempt.
+struct AppliedUpdate {
+    email: Option<String>,
+    previous_level: i32,
+    level: i32,
+    new_badges: Vec<String>,
+    newly_verified: bool,
+    verified: bool,
+    held_for_review: bool,
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
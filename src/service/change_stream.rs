@@ -0,0 +1,326 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_util::stream::TryStreamExt;
+use mongodb::Client;
+use mongodb::bson::{Document, doc};
+use mongodb::change_stream::ChangeStream;
+use mongodb::change_stream::event::{ChangeStreamEvent, OperationType, ResumeToken};
+use mongodb::error::ErrorKind;
+use mongodb::options::{FullDocumentBeforeChangeType, FullDocumentType};
+use tracing::{error, info, warn};
+
+use crate::error::Error;
+use crate::model::level::LevelRequest;
+use crate::queue::BadgeUpdateQueue;
+use crate::service::schema::SchemaMapping;
+
+const RESUME_TOKEN_COLLECTION: &str = "BadgeForgeResumeToken";
+const RESUME_TOKEN_ID: &str = "recipe_watcher";
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+/// How often the resume token is persisted while events are handled.
+const RESUME_TOKEN_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+/// Server error code when a resume token is no longer in the oplog.
+const CHANGE_STREAM_HISTORY_LOST: i32 = 286;
+
+/// Whether the watched collection reports the owner of deleted recipes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreImages {
+    /// MongoDB before 6.0 has no pre-images and rejects streams that ask for them.
+    Unsupported,
+    /// `changeStreamPreAndPostImages` is off for the collection, so deletes are skipped.
+    Disabled,
+    Enabled,
+}
+
+impl PreImages {
+    /// Classifies the `buildInfo` version of the server and the collection's pre-image option.
+    /// Versions that cannot be parsed are assumed to be recent.
+    pub fn detect(server_version: &str, enabled_on_collection: bool) -> Self {
+        let major = server_version
+            .split('.')
+            .next()
+            .and_then(|major| major.parse::<u32>().ok());
+        match major {
+            Some(major) if major < 6 => Self::Unsupported,
+            _ if enabled_on_collection => Self::Enabled,
+            _ => Self::Disabled,
+        }
+    }
+}
+
+/// Watches the recipe collection and enqueues a badge update for the owner of every
+/// inserted, deleted or re-liked recipe.
+///
+/// The resume token is persisted every few seconds, and only covers events whose request
+/// was queued, so events are delivered at least once across restarts.
+pub struct RecipeWatcher {
+    client: Client,
+    db_name: String,
+    queue: Arc<dyn BadgeUpdateQueue>,
+    schema: SchemaMapping,
+}
+
+impl RecipeWatcher {
+    pub fn new(client: Client, db_name: String, queue: Arc<dyn BadgeUpdateQueue>) -> Self {
+        Self {
+            client,
+            db_name,
+            queue,
+            schema: SchemaMapping::default(),
+        }
+    }
+
+    pub fn with_schema(mut self, schema: SchemaMapping) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    pub async fn start(self) {
+        tokio::spawn(async move {
+            info!("Recipe change stream watcher started");
+            let mut retry_delay = Duration::from_secs(1);
+            let pre_images = loop {
+                match self.pre_images().await {
+                    Ok(pre_images) => break pre_images,
+                    Err(e) => {
+                        error!(
+                            "Checking recipe pre-images failed, retrying in {:?}: {}",
+                            retry_delay, e
+                        );
+                        tokio::time::sleep(retry_delay).await;
+                        retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                    }
+                }
+            };
+            match pre_images {
+                PreImages::Unsupported => warn!(
+                    "MongoDB before 6.0 has no change stream pre-images, badges are not \
+                     recomputed after recipes of {} are deleted",
+                    self.schema.recipe_collection
+                ),
+                PreImages::Disabled => warn!(
+                    "changeStreamPreAndPostImages is disabled on {}, badges are not recomputed \
+                     after its recipes are deleted",
+                    self.schema.recipe_collection
+                ),
+                PreImages::Enabled => {}
+            }
+
+            retry_delay = Duration::from_secs(1);
+            loop {
+                match self.watch(pre_images).await {
+                    Ok(()) => {
+                        info!("Recipe change stream closed, reopening");
+                        retry_delay = Duration::from_secs(1);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Recipe change stream failed, retrying in {:?}: {}",
+                            retry_delay, e
+                        );
+                        tokio::time::sleep(retry_delay).await;
+                        retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Checks the server version and the recipe collection's options for pre-images.
+    async fn pre_images(&self) -> Result<PreImages, Error> {
+        let build_info = self
+            .client
+            .database("admin")
+            .run_command(doc! { "buildInfo": 1 })
+            .await?;
+        let version = build_info.get_str("version").unwrap_or_default();
+
+        let collection = self
+            .client
+            .database(&self.db_name)
+            .list_collections()
+            .filter(doc! { "name": &self.schema.recipe_collection })
+            .await?
+            .try_next()
+            .await?;
+        let enabled = collection
+            .and_then(|collection| collection.options.change_stream_pre_and_post_images)
+            .is_some_and(|pre_images| pre_images.enabled);
+
+        Ok(PreImages::detect(version, enabled))
+    }
+
+    async fn watch(&self, pre_images: PreImages) -> Result<(), Error> {
+        let mut saved_token = self.load_resume_token().await?;
+        if saved_token.is_some() {
+            info!("Resuming recipe change stream from the persisted token");
+        }
+
+        let mut stream = match self.open(saved_token.clone(), pre_images).await {
+            Ok(stream) => stream,
+            Err(e) if is_history_lost(&e) => return self.forget_resume_token().await,
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut saved_at = Instant::now();
+        while stream.is_alive() {
+            // Returns `None` when a batch comes back empty, so the token is saved while idle
+            let event = match stream.next_if_any().await {
+                Ok(event) => event,
+                Err(e) if is_history_lost(&e) => return self.forget_resume_token().await,
+                Err(e) => return Err(e.into()),
+            };
+            if let Some(request) = event
+                .as_ref()
+                .and_then(|event| level_request_from_event(event, &self.schema))
+            {
+                self.queue.enqueue(request).await?;
+            }
+
+            // The stream's token only moves past events that were handled above
+            let token = stream.resume_token();
+            if saved_at.elapsed() >= RESUME_TOKEN_SAVE_INTERVAL && token != saved_token {
+                if let Some(token) = &token {
+                    self.save_resume_token(token).await?;
+                }
+                saved_token = token;
+                saved_at = Instant::now();
+            }
+        }
+
+        match stream.resume_token() {
+            Some(token) if Some(&token) != saved_token.as_ref() => {
+                self.save_resume_token(&token).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn open(
+        &self,
+        resume_token: Option<ResumeToken>,
+        pre_images: PreImages,
+    ) -> mongodb::error::Result<ChangeStream<ChangeStreamEvent<Document>>> {
+        let likes_updated = format!(
+            "updateDescription.updatedFields.{}",
+            self.schema.recipe.num_likes
+        );
+        let collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<Document>(&self.schema.recipe_collection);
+        let mut watch = collection
+            .watch()
+            .pipeline(vec![doc! {
+                "$match": {
+                    "$or": [
+                        { "operationType": { "$in": ["insert", "replace", "delete"] } },
+                        {
+                            "operationType": "update",
+                            likes_updated: { "$exists": true }
+                        }
+                    ]
+                }
+            }])
+            .full_document(FullDocumentType::UpdateLookup)
+            .resume_after(resume_token);
+        // Servers before 6.0 fail the whole stream when asked for pre-images
+        if pre_images != PreImages::Unsupported {
+            watch = watch.full_document_before_change(FullDocumentBeforeChangeType::WhenAvailable);
+        }
+        watch.await
+    }
+
+    /// Drops a resume token that fell off the oplog, so the stream restarts from the current
+    /// time instead of failing on every reopen.
+    async fn forget_resume_token(&self) -> Result<(), Error> {
+        warn!(
+            "Recipe change stream history lost, recipe changes since the persisted token were \
+             missed; restarting from the current time"
+        );
+        self.client
+            .database(&self.db_name)
+            .collection::<Document>(RESUME_TOKEN_COLLECTION)
+            .delete_one(doc! { "_id": RESUME_TOKEN_ID })
+            .await
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    async fn load_resume_token(&self) -> Result<Option<ResumeToken>, Error> {
+        let stored = self
+            .client
+            .database(&self.db_name)
+            .collection::<Document>(RESUME_TOKEN_COLLECTION)
+            .find_one(doc! { "_id": RESUME_TOKEN_ID })
+            .await?;
+
+        match stored.and_then(|doc| doc.get("token").cloned()) {
+            Some(token) => mongodb::bson::from_bson(token)
+                .map(Some)
+                .map_err(Error::from),
+            None => Ok(None),
+        }
+    }
+
+    async fn save_resume_token(&self, token: &ResumeToken) -> Result<(), Error> {
+        let token = mongodb::bson::to_bson(token)?;
+
+        self.client
+            .database(&self.db_name)
+            .collection::<Document>(RESUME_TOKEN_COLLECTION)
+            .update_one(
+                doc! { "_id": RESUME_TOKEN_ID },
+                doc! { "$set": { "token": token, "updatedAt": mongodb::bson::DateTime::now() } },
+            )
+            .upsert(true)
+            .await
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+}
+
+/// Whether `error` reports that the resume token fell off the oplog, which retrying cannot fix.
+pub fn is_history_lost(error: &mongodb::error::Error) -> bool {
+    matches!(
+        error.kind.as_ref(),
+        ErrorKind::Command(command) if command.code == CHANGE_STREAM_HISTORY_LOST
+    )
+}
+
+/// Translates a recipe change event into a badge update for the recipe's owner.
+///
+/// Updates only count when the mapped likes field changed. Deletes need the pre-image of the recipe,
+/// which MongoDB only provides when `changeStreamPreAndPostImages` is enabled on the collection.
+pub fn level_request_from_event(
+    event: &ChangeStreamEvent<Document>,
+    schema: &SchemaMapping,
+) -> Option<LevelRequest> {
+    let recipe = match event.operation_type {
+        OperationType::Insert | OperationType::Replace => event.full_document.as_ref(),
+        OperationType::Update => {
+            let likes_changed = event
+                .update_description
+                .as_ref()
+                .is_some_and(|update| update.updated_fields.contains_key(&schema.recipe.num_likes));
+            if !likes_changed {
+                return None;
+            }
+            event
+                .full_document
+                .as_ref()
+                .or(event.full_document_before_change.as_ref())
+        }
+        OperationType::Delete => event.full_document_before_change.as_ref(),
+        _ => None,
+    }?;
+
+    let user_id = recipe.get_object_id(&schema.recipe.user_id).ok()?;
+
+    Some(LevelRequest {
+        user_id: user_id.to_hex(),
+        request_id: uuid::Uuid::new_v4().to_string(),
+        created_at: chrono::Utc::now(),
+    })
+}
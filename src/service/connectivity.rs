@@ -0,0 +1,188 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::config::ConfigSource;
+use crate::error::Error;
+use crate::service::db::Database;
+
+/// How long a single ping may take before the database counts as unreachable.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whether the database is reachable, shared by everything that depends on it.
+///
+/// Clones observe the same state.
+#[derive(Clone)]
+pub struct Connectivity {
+    state: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Connectivity {
+    /// Always ready, for setups without a connectivity monitor.
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl Connectivity {
+    pub fn new(ready: bool) -> Self {
+        Self {
+            state: Arc::new(watch::Sender::new(ready)),
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        *self.state.borrow()
+    }
+
+    pub fn set_ready(&self, ready: bool) {
+        let changed = self.state.send_if_modified(|state| {
+            let changed = *state != ready;
+            *state = ready;
+            changed
+        });
+        if changed {
+            if ready {
+                info!("Database is reachable, resuming");
+            } else {
+                warn!("Database is unreachable, pausing badge processing");
+            }
+        }
+    }
+
+    pub async fn wait_until_ready(&self) {
+        let mut receiver = self.state.subscribe();
+        // The sender is owned by `self`, so the channel cannot close while waiting
+        let _ = receiver.wait_for(|ready| *ready).await;
+    }
+}
+
+/// Startup and monitoring settings, read from `DB_STARTUP_TIMEOUT_SECS` and
+/// `DB_CHECK_INTERVAL_SECS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectivityConfig {
+    /// How long startup keeps retrying before giving up. Zero retries forever.
+    pub startup_timeout: Duration,
+    /// Time between pings while the database is reachable.
+    pub check_interval: Duration,
+    /// First delay between attempts while the database is unreachable, doubled up to `max_backoff`.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for ConnectivityConfig {
+    fn default() -> Self {
+        Self {
+            startup_timeout: Duration::from_secs(60),
+            check_interval: Duration::from_secs(10),
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ConnectivityConfig {
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let defaults = Self::default();
+        let check_interval = source.u32(
+            "DB_CHECK_INTERVAL_SECS",
+            defaults.check_interval.as_secs() as u32,
+        )?;
+        if check_interval == 0 {
+            return Err("DB_CHECK_INTERVAL_SECS must be at least 1".to_string());
+        }
+        Ok(Self {
+            startup_timeout: Duration::from_secs(source.u32(
+                "DB_STARTUP_TIMEOUT_SECS",
+                defaults.startup_timeout.as_secs() as u32,
+            )? as u64),
+            check_interval: Duration::from_secs(check_interval as u64),
+            ..defaults
+        })
+    }
+}
+
+/// Pings the database at startup until it answers, then keeps checking it in the background
+/// and updates the shared `Connectivity`.
+pub struct ConnectivityMonitor {
+    db: Arc<dyn Database>,
+    connectivity: Connectivity,
+    config: ConnectivityConfig,
+}
+
+impl ConnectivityMonitor {
+    pub fn new(
+        db: Arc<dyn Database>,
+        connectivity: Connectivity,
+        config: ConnectivityConfig,
+    ) -> Self {
+        Self {
+            db,
+            connectivity,
+            config,
+        }
+    }
+
+    /// Retries with backoff until the database answers or `startup_timeout` elapses. Does not
+    /// mark the connectivity ready, so callers can finish their own setup first.
+    pub async fn wait_for_startup(&self) -> Result<(), Error> {
+        let deadline = (!self.config.startup_timeout.is_zero())
+            .then(|| Instant::now() + self.config.startup_timeout);
+        let mut backoff = self.config.initial_backoff;
+        loop {
+            let error = match self.ping().await {
+                Ok(()) => return Ok(()),
+                Err(e) if !e.is_retryable() => return Err(e),
+                Err(e) => e,
+            };
+            if deadline.is_some_and(|deadline| Instant::now() + backoff > deadline) {
+                return Err(Error::Transient(format!(
+                    "Database unreachable after {:?}: {}",
+                    self.config.startup_timeout, error
+                )));
+            }
+            warn!(
+                "Database not reachable yet, retrying in {:?}: {}",
+                backoff, error
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.config.max_backoff);
+        }
+    }
+
+    /// Pings the database forever, checking every `check_interval` while it is reachable
+    /// and with backoff while it is not.
+    pub async fn run(self) {
+        let mut backoff = self.config.initial_backoff;
+        loop {
+            match self.ping().await {
+                Ok(()) => {
+                    self.connectivity.set_ready(true);
+                    backoff = self.config.initial_backoff;
+                    tokio::time::sleep(self.config.check_interval).await;
+                }
+                Err(e) => {
+                    if self.connectivity.is_ready() {
+                        warn!("Database ping failed: {}", e);
+                    }
+                    self.connectivity.set_ready(false);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.config.max_backoff);
+                }
+            }
+        }
+    }
+
+    async fn ping(&self) -> Result<(), Error> {
+        match tokio::time::timeout(PING_TIMEOUT, self.db.ping()).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::Transient(format!(
+                "Database ping timed out after {:?}",
+                PING_TIMEOUT
+            ))),
+        }
+    }
+}
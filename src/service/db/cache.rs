@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use super::{BadgeUpdate, Database};
+use crate::config::ConfigSource;
+use crate::error::Error;
+use crate::model::award::{AwardOutcome, TopRecipeAward};
+use crate::model::recipe::Recipe;
+use crate::model::review::{BadgeReview, ReviewStatus};
+use crate::model::stats::UserStats;
+use crate::model::user::User;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+
+/// Settings of the read-through cache, read from `DB_CACHE_TTL_SECS` and `DB_CACHE_MAX_USERS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// How long cached data is served. A zero TTL disables the cache.
+    pub ttl: Duration,
+    /// Users kept at most; the entries closest to expiry are evicted first, a tenth of the
+    /// cache at a time.
+    pub max_users: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::ZERO,
+            max_users: 10_000,
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let defaults = Self::default();
+        Ok(Self {
+            ttl: Duration::from_secs(source.u32("DB_CACHE_TTL_SECS", 0)? as u64),
+            max_users: source.u32("DB_CACHE_MAX_USERS", defaults.max_users as u32)? as usize,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero() && self.max_users > 0
+    }
+}
+
+/// Counters shared between a `CachedDatabase` and whoever reports on it.
+#[derive(Debug, Default)]
+pub struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+    evictions: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+    pub evictions: u64,
+}
+
+impl CacheCounters {
+    pub fn snapshot(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+struct Cached<T> {
+    value: T,
+    /// When the query that produced the value was started.
+    loaded_at: DateTime<Utc>,
+    expires_at: Instant,
+}
+
+/// What is cached for a single user. Each part is loaded and expires on its own.
+#[derive(Default)]
+struct CacheEntry {
+    user: Option<Cached<User>>,
+    stats: Option<Cached<UserStats>>,
+    recipes: Option<Cached<Vec<Recipe>>>,
+    /// Generation of the last write to the user through the cache.
+    written: u64,
+}
+
+impl CacheEntry {
+    fn expires_at(&self) -> Option<Instant> {
+        [
+            self.user.as_ref().map(|c| c.expires_at),
+            self.stats.as_ref().map(|c| c.expires_at),
+            self.recipes.as_ref().map(|c| c.expires_at),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+    }
+}
+
+/// `Database` decorator caching users and their recipe aggregates for a short time, so bursts
+/// of updates for the same user reuse one query instead of repeating it for every like.
+///
+/// Writes made through the decorator invalidate the cached user. Recipes are only written by
+/// the host application, so the processor drops recipe stats and lists loaded before its
+/// request was created through [`Database::expire_aggregates`].
+///
+/// Every write and invalidation takes a new generation. A load is only cached when no write to
+/// its user got a newer generation while the query ran, so a slow read cannot put back data
+/// an overlapping write just invalidated.
+pub struct CachedDatabase {
+    inner: Arc<dyn Database>,
+    config: CacheConfig,
+    entries: Mutex<HashMap<ObjectId, CacheEntry>>,
+    counters: Arc<CacheCounters>,
+    generation: AtomicU64,
+    /// Newest generation of a write whose entry is gone, which applies to every user since
+    /// the entry no longer records it.
+    forgotten: AtomicU64,
+}
+
+impl CachedDatabase {
+    pub fn new(inner: Arc<dyn Database>, config: CacheConfig) -> Self {
+        Self {
+            inner,
+            config,
+            entries: Mutex::new(HashMap::new()),
+            counters: Arc::new(CacheCounters::default()),
+            generation: AtomicU64::new(0),
+            forgotten: AtomicU64::new(0),
+        }
+    }
+
+    pub fn counters(&self) -> Arc<CacheCounters> {
+        self.counters.clone()
+    }
+
+    /// Drops everything cached for a user, e.g. after an out-of-band change.
+    pub fn invalidate(&self, user_id: &ObjectId) {
+        let mut entries = self.lock();
+        let generation = self.next_generation();
+        self.forgotten.fetch_max(generation, Ordering::SeqCst);
+        if entries.remove(user_id).is_some() {
+            self.counters.invalidations.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Drops the cached user document but keeps the recipe aggregates, which badge_forge
+    /// never writes.
+    fn invalidate_user(&self, user_id: &ObjectId) {
+        let mut entries = self.lock();
+        let generation = self.next_generation();
+        let Some(entry) = entries.get_mut(user_id) else {
+            self.forgotten.fetch_max(generation, Ordering::SeqCst);
+            return;
+        };
+        entry.written = generation;
+        if entry.user.take().is_some() {
+            self.counters.invalidations.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn next_generation(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// The generation a load has to be taken at, before its query starts.
+    fn current_generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<ObjectId, CacheEntry>> {
+        // Entries are replaced as a whole, so a panic cannot leave one half-written
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn get<T: Clone>(
+        &self,
+        user_id: &ObjectId,
+        part: impl Fn(&CacheEntry) -> &Option<Cached<T>>,
+    ) -> Option<T> {
+        let entries = self.lock();
+        let value = entries
+            .get(user_id)
+            .and_then(|entry| part(entry).as_ref())
+            .filter(|cached| cached.expires_at > Instant::now())
+            .map(|cached| cached.value.clone());
+        self.counters.record(value.is_some());
+        value
+    }
+
+    /// Caches a value loaded at `generation`, unless a write to the user got a newer one
+    /// while it was loading.
+    fn put<T>(
+        &self,
+        user_id: &ObjectId,
+        value: T,
+        loaded_at: DateTime<Utc>,
+        generation: u64,
+        part: impl Fn(&mut CacheEntry) -> &mut Option<Cached<T>>,
+    ) {
+        let now = Instant::now();
+        let mut entries = self.lock();
+        let written = entries.get(user_id).map_or(0, |entry| entry.written);
+        if written.max(self.forgotten.load(Ordering::SeqCst)) > generation {
+            return;
+        }
+        if !entries.contains_key(user_id) && entries.len() >= self.config.max_users {
+            self.evict(&mut entries, now);
+        }
+        *part(entries.entry(*user_id).or_default()) = Some(Cached {
+            value,
+            loaded_at,
+            expires_at: now + self.config.ttl,
+        });
+    }
+
+    /// Removes expired entries, then the ones closest to expiry until a tenth of the cache is
+    /// free, so a full cache is not scanned on every insert.
+    fn evict(&self, entries: &mut HashMap<ObjectId, CacheEntry>, now: Instant) {
+        let before = entries.len();
+        let mut forgotten = 0;
+        entries.retain(|_, entry| {
+            let keep = entry.expires_at().is_some_and(|at| at > now);
+            if !keep {
+                forgotten = forgotten.max(entry.written);
+            }
+            keep
+        });
+
+        let target = self.config.max_users - self.config.max_users.div_ceil(10);
+        if entries.len() > target {
+            let excess = entries.len() - target;
+            let mut by_expiry: Vec<_> = entries
+                .iter()
+                .map(|(id, entry)| (entry.expires_at(), *id))
+                .collect();
+            by_expiry.select_nth_unstable(excess - 1);
+            for (_, id) in &by_expiry[..excess] {
+                if let Some(entry) = entries.remove(id) {
+                    forgotten = forgotten.max(entry.written);
+                }
+            }
+        }
+
+        self.forgotten.fetch_max(forgotten, Ordering::SeqCst);
+        self.counters
+            .evictions
+            .fetch_add((before - entries.len()) as u64, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl Database for CachedDatabase {
+    async fn ping(&self) -> Result<(), Error> {
+        self.inner.ping().await
+    }
+
+    async fn find_user(&self, user_id: &ObjectId) -> Result<Option<User>, Error> {
+        if let Some(user) = self.get(user_id, |entry| &entry.user) {
+            return Ok(Some(user));
+        }
+        // Unknown users are not cached, they may be created at any time
+        let generation = self.current_generation();
+        let loaded_at = Utc::now();
+        let user = self.inner.find_user(user_id).await?;
+        if let Some(ref user) = user {
+            self.put(user_id, user.clone(), loaded_at, generation, |entry| {
+                &mut entry.user
+            });
+        }
+        Ok(user)
+    }
+
+    async fn apply_badge_update(
+        &self,
+        user_id: &ObjectId,
+        update: &BadgeUpdate,
+    ) -> Result<bool, Error> {
+        // Invalidate even when the write is rejected, the cached user is outdated then
+        let result = self.inner.apply_badge_update(user_id, update).await;
+        self.invalidate_user(user_id);
+        result
+    }
+
+    async fn get_user_recipes(&self, user_id: &ObjectId) -> Result<Vec<Recipe>, Error> {
+        if let Some(recipes) = self.get(user_id, |entry| &entry.recipes) {
+            return Ok(recipes);
+        }
+        let generation = self.current_generation();
+        let loaded_at = Utc::now();
+        let recipes = self.inner.get_user_recipes(user_id).await?;
+        self.put(user_id, recipes.clone(), loaded_at, generation, |entry| {
+            &mut entry.recipes
+        });
+        Ok(recipes)
+    }
+
+    async fn get_user_stats(&self, user_id: &ObjectId) -> Result<UserStats, Error> {
+        if let Some(stats) = self.get(user_id, |entry| &entry.stats) {
+            return Ok(stats);
+        }
+        let generation = self.current_generation();
+        let loaded_at = Utc::now();
+        let stats = self.inner.get_user_stats(user_id).await?;
+        self.put(user_id, stats, loaded_at, generation, |entry| {
+            &mut entry.stats
+        });
+        Ok(stats)
+    }
+
+    fn expire_aggregates(&self, user_id: &ObjectId, loaded_before: DateTime<Utc>) {
+        let mut entries = self.lock();
+        let Some(entry) = entries.get_mut(user_id) else {
+            return;
+        };
+        let stale_stats = entry
+            .stats
+            .take_if(|cached| cached.loaded_at < loaded_before)
+            .is_some();
+        let stale_recipes = entry
+            .recipes
+            .take_if(|cached| cached.loaded_at < loaded_before)
+            .is_some();
+        if stale_stats || stale_recipes {
+            self.counters.invalidations.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    async fn add_badge_to_user(
+        &self,
+        user_id: &ObjectId,
+        badge: &str,
+    ) -> Result<Option<bool>, Error> {
+        let result = self.inner.add_badge_to_user(user_id, badge).await;
+        self.invalidate_user(user_id);
+        result
+    }
+
+    async fn record_top_recipe_award(
+        &self,
+        user_id: &ObjectId,
+        award: &TopRecipeAward,
+    ) -> Result<Option<AwardOutcome>, Error> {
+        let result = self.inner.record_top_recipe_award(user_id, award).await;
+        self.invalidate_user(user_id);
+        result
+    }
+
+    async fn set_verification(
+        &self,
+        user_id: &ObjectId,
+        verified: bool,
+        locked: bool,
+        reason: &str,
+        updated_by: &str,
+    ) -> Result<bool, Error> {
+        let result = self
+            .inner
+            .set_verification(user_id, verified, locked, reason, updated_by)
+            .await;
+        self.invalidate_user(user_id);
+        result
+    }
+
+    async fn hold_badges_for_review(&self, review: &BadgeReview) -> Result<(), Error> {
+        self.inner.hold_badges_for_review(review).await
+    }
+
+    async fn get_pending_badge_reviews(&self) -> Result<Vec<BadgeReview>, Error> {
+        self.inner.get_pending_badge_reviews().await
+    }
+
+    async fn get_user_badge_reviews(&self, user_id: &ObjectId) -> Result<Vec<BadgeReview>, Error> {
+        self.inner.get_user_badge_reviews(user_id).await
+    }
+
+    async fn resolve_badge_review(
+        &self,
+        review_id: &ObjectId,
+        status: ReviewStatus,
+        resolved_by: &str,
+    ) -> Result<Option<BadgeReview>, Error> {
+        self.inner
+            .resolve_badge_review(review_id, status, resolved_by)
+            .await
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use super::{BadgeUpdate, Database};
+use crate::error::Error;
+use crate::model::award::{AwardOutcome, TopRecipeAward};
+use crate::model::recipe::Recipe;
+use crate::model::review::{BadgeReview, ReviewStatus};
+use crate::model::stats::UserStats;
+use crate::model::user::User;
+use async_trait::async_trait;
+use mongodb::bson::{Bson, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+/// Contents of a fixture or persistence file, stored as MongoDB extended JSON so ids and
+/// dates can be written as `{ "$oid": "..." }` and `{ "$date": "..." }`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemorySnapshot {
+    #[serde(default)]
+    pub users: Vec<User>,
+    #[serde(default)]
+    pub recipes: Vec<Recipe>,
+    #[serde(default)]
+    pub reviews: Vec<BadgeReview>,
+}
+
+impl MemorySnapshot {
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| Error::Validation(format!("Invalid JSON: {}", e)))?;
+        let bson = Bson::try_from(value)
+            .map_err(|e| Error::Validation(format!("Invalid extended JSON: {}", e)))?;
+        mongodb::bson::from_bson(bson)
+            .map_err(|e| Error::Validation(format!("Invalid snapshot: {}", e)))
+    }
+
+    pub fn to_json(&self) -> Result<String, Error> {
+        let bson = mongodb::bson::to_bson(self)?;
+        serde_json::to_string_pretty(&bson.into_relaxed_extjson())
+            .map_err(|e| Error::Permanent(format!("Serialization error: {}", e)))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| Error::Permanent(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::from_json(&json)
+            .map_err(|e| Error::Validation(format!("{}: {}", path.display(), e.public_message())))
+    }
+}
+
+#[derive(Default, Clone)]
+struct MemoryState {
+    users: HashMap<ObjectId, User>,
+    recipes: HashMap<ObjectId, Vec<Recipe>>,
+    reviews: Vec<BadgeReview>,
+}
+
+/// `Database` kept in process memory, for running badge_forge without MongoDB.
+///
+/// With a persistence path every write saves the whole state to that file, which is
+/// fine for local development but not meant for production data volumes. A write only
+/// takes effect once it is saved, so a failed save leaves memory and file unchanged.
+pub struct MemoryDatabase {
+    state: Mutex<MemoryState>,
+    persist_path: Option<PathBuf>,
+    /// Held by a persisted write from copying the state until the saved copy replaces it,
+    /// so concurrent writes cannot overwrite each other.
+    writer: tokio::sync::Mutex<()>,
+}
+
+impl Default for MemoryDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryDatabase {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(MemoryState::default()),
+            persist_path: None,
+            writer: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn from_snapshot(snapshot: MemorySnapshot) -> Self {
+        let db = Self::new();
+        {
+            let mut state = db.lock();
+            for user in snapshot.users {
+                state.users.insert(user._id, user);
+            }
+            for recipe in snapshot.recipes {
+                state
+                    .recipes
+                    .entry(recipe.user_id)
+                    .or_default()
+                    .push(recipe);
+            }
+            state.reviews = snapshot.reviews;
+        }
+        db
+    }
+
+    /// Saves the state to `path` after every write. An existing file at `path` takes
+    /// precedence over any seed data and is loaded instead.
+    pub fn with_persistence(mut self, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        if path.exists() {
+            let loaded = Self::from_snapshot(MemorySnapshot::load(&path)?);
+            self.state = loaded.state;
+        }
+        write_snapshot(&path, &Self::snapshot_of(&self.lock()))?;
+        self.persist_path = Some(path);
+        Ok(self)
+    }
+
+    /// Adds or replaces a user. Like `insert_recipe`, this is meant for seeding and is
+    /// only written to the persistence file with the next write through `Database`. It
+    /// must not run concurrently with those writes, whose saved copy would replace it.
+    pub fn insert_user(&self, user: User) {
+        let mut state = self.lock();
+        state.users.insert(user._id, user);
+    }
+
+    pub fn insert_recipe(&self, recipe: Recipe) {
+        let mut state = self.lock();
+        state
+            .recipes
+            .entry(recipe.user_id)
+            .or_default()
+            .push(recipe);
+    }
+
+    pub fn snapshot(&self) -> MemorySnapshot {
+        Self::snapshot_of(&self.lock())
+    }
+
+    fn snapshot_of(state: &MemoryState) -> MemorySnapshot {
+        let mut users: Vec<User> = state.users.values().cloned().collect();
+        users.sort_by_key(|user| user._id);
+        let mut recipes: Vec<Recipe> = state.recipes.values().flatten().cloned().collect();
+        recipes.sort_by_key(|recipe| recipe._id);
+        MemorySnapshot {
+            users,
+            recipes,
+            reviews: state.reviews.clone(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MemoryState> {
+        // A panic while holding the lock cannot leave the maps half-updated in a way
+        // that matters for a development backend, so recover instead of propagating it.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Applies a write. `change` returns its result and whether it changed the state.
+    ///
+    /// With persistence the change is made to a copy of the state, which is saved on the
+    /// blocking thread pool and only then replaces the state. The state lock is not held
+    /// while saving, so reads are not blocked by the file system.
+    async fn write<T>(
+        &self,
+        change: impl FnOnce(&mut MemoryState) -> (T, bool),
+    ) -> Result<T, Error> {
+        let Some(path) = self.persist_path.clone() else {
+            return Ok(change(&mut self.lock()).0);
+        };
+
+        let _writer = self.writer.lock().await;
+        let mut next = self.lock().clone();
+        let (result, changed) = change(&mut next);
+        if changed {
+            let snapshot = Self::snapshot_of(&next);
+            tokio::task::spawn_blocking(move || write_snapshot(&path, &snapshot))
+                .await
+                .map_err(|e| Error::Permanent(format!("Persisting the state failed: {}", e)))??;
+            *self.lock() = next;
+        }
+        Ok(result)
+    }
+}
+
+/// Writes a snapshot to the persistence file, through a temporary file so a crash never
+/// leaves a truncated snapshot behind.
+fn write_snapshot(path: &Path, snapshot: &MemorySnapshot) -> Result<(), Error> {
+    let json = snapshot.to_json()?;
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, json)
+        .and_then(|_| std::fs::rename(&tmp_path, path))
+        .map_err(|e| Error::Permanent(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+#[async_trait]
+impl Database for MemoryDatabase {
+    async fn find_user(&self, user_id: &ObjectId) -> Result<Option<User>, Error> {
+        Ok(self.lock().users.get(user_id).cloned())
+    }
+
+    async fn apply_badge_update(
+        &self,
+        user_id: &ObjectId,
+        update: &BadgeUpdate,
+    ) -> Result<bool, Error> {
+        self.write(|state| {
+            let Some(user) = state.users.get_mut(user_id) else {
+                return (false, false);
+            };
+            let locked = user.verification_locked.unwrap_or(false);
+            if user.level != update.expected_level || (update.verified.is_some() && locked) {
+                return (false, false);
+            }
+
+            user.level = update.level;
+            for badge in &update.add_badges {
+                if !user.badges.contains(badge) {
+                    user.badges.push(badge.clone());
+                }
+            }
+            if let Some(verified) = update.verified {
+                user.verified = Some(verified);
+            }
+            (true, true)
+        })
+        .await
+    }
+
+    async fn get_user_recipes(&self, user_id: &ObjectId) -> Result<Vec<Recipe>, Error> {
+        Ok(self
+            .lock()
+            .recipes
+            .get(user_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_user_stats(&self, user_id: &ObjectId) -> Result<UserStats, Error> {
+        let state = self.lock();
+        Ok(UserStats::from_recipes(
+            state
+                .recipes
+                .get(user_id)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+        ))
+    }
+
+    async fn add_badge_to_user(
+        &self,
+        user_id: &ObjectId,
+        badge: &str,
+    ) -> Result<Option<bool>, Error> {
+        self.write(|state| {
+            let Some(user) = state.users.get_mut(user_id) else {
+                return (None, false);
+            };
+            if user.badges.iter().any(|b| b == badge) {
+                return (Some(false), false);
+            }
+            user.badges.push(badge.to_string());
+            (Some(true), true)
+        })
+        .await
+    }
+
+    async fn record_top_recipe_award(
+        &self,
+        user_id: &ObjectId,
+        award: &TopRecipeAward,
+    ) -> Result<Option<AwardOutcome>, Error> {
+        self.write(|state| {
+            let Some(user) = state.users.get_mut(user_id) else {
+                return (None, false);
+            };
+
+            let already_recorded = user
+                .top_recipe_awards
+                .iter()
+                .any(|a| a.badge == award.badge && a.period == award.period);
+            if !already_recorded {
+                user.top_recipe_awards.push(award.clone());
+                if !user.badges.contains(&award.badge) {
+                    user.badges.push(award.badge.clone());
+                }
+            }
+            let outcome = AwardOutcome {
+                newly_awarded: !already_recorded,
+                win_count: user.top_recipe_wins(&award.badge),
+            };
+            (Some(outcome), !already_recorded)
+        })
+        .await
+    }
+
+    async fn set_verification(
+        &self,
+        user_id: &ObjectId,
+        verified: bool,
+        locked: bool,
+        reason: &str,
+        updated_by: &str,
+    ) -> Result<bool, Error> {
+        self.write(|state| {
+            let Some(user) = state.users.get_mut(user_id) else {
+                return (false, false);
+            };
+            user.verified = Some(verified);
+            user.verification_locked = Some(locked);
+            user.verification_reason = Some(reason.to_string());
+            user.verification_updated_by = Some(updated_by.to_string());
+            (true, true)
+        })
+        .await
+    }
+
+    async fn hold_badges_for_review(&self, review: &BadgeReview) -> Result<(), Error> {
+        self.write(|state| {
+            if let Some(pending) = state
+                .reviews
+                .iter_mut()
+                .find(|r| r.user_id == review.user_id && r.status == ReviewStatus::Pending)
+            {
+                for badge in &review.badges {
+                    if !pending.badges.contains(badge) {
+                        pending.badges.push(badge.clone());
+                    }
+                }
+                pending.verify |= review.verify;
+                pending.signals = review.signals.clone();
+                pending.score = review.score;
+            } else {
+                state.reviews.push(review.clone());
+            }
+            ((), true)
+        })
+        .await
+    }
+
+    async fn get_pending_badge_reviews(&self) -> Result<Vec<BadgeReview>, Error> {
+        let state = self.lock();
+        let mut reviews: Vec<BadgeReview> = state
+            .reviews
+            .iter()
+            .filter(|r| r.status == ReviewStatus::Pending)
+            .cloned()
+            .collect();
+        reviews.sort_by_key(|r| r.created_at);
+        Ok(reviews)
+    }
+
+    async fn get_user_badge_reviews(&self, user_id: &ObjectId) -> Result<Vec<BadgeReview>, Error> {
+        let state = self.lock();
+        let mut reviews: Vec<BadgeReview> = state
+            .reviews
+            .iter()
+            .filter(|r| r.user_id == *user_id)
+            .cloned()
+            .collect();
+        reviews.sort_by_key(|r| r.created_at);
+        Ok(reviews)
+    }
+
+    async fn resolve_badge_review(
+        &self,
+        review_id: &ObjectId,
+        status: ReviewStatus,
+        resolved_by: &str,
+    ) -> Result<Option<BadgeReview>, Error> {
+        self.write(|state| {
+            let Some(review) = state
+                .reviews
+                .iter_mut()
+                .find(|r| r._id == *review_id && r.status == ReviewStatus::Pending)
+            else {
+                return (None, false);
+            };
+            review.status = status;
+            review.resolved_at = Some(chrono::Utc::now());
+            review.resolved_by = Some(resolved_by.to_string());
+            (Some(review.clone()), true)
+        })
+        .await
+    }
+}
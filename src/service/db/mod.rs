@@ -0,0 +1,125 @@
+use crate::config::ConfigSource;
+use crate::error::Error;
+use crate::model::award::{AwardOutcome, TopRecipeAward};
+use crate::model::recipe::Recipe;
+use crate::model::review::{BadgeReview, ReviewStatus};
+use crate::model::stats::UserStats;
+use crate::model::user::User;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+
+mod cache;
+mod memory;
+mod mongo;
+mod sqlite;
+
+pub use cache::{CacheConfig, CacheCounters, CacheStats, CachedDatabase};
+pub use memory::{MemoryDatabase, MemorySnapshot};
+pub use mongo::MongoDatabase;
+pub use sqlite::SqliteDatabase;
+
+/// Storage used by the service, read from `DATABASE_BACKEND`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DatabaseBackend {
+    #[default]
+    Mongo,
+    /// In-process storage for local development, see `MemoryDatabase`.
+    Memory,
+    /// Single-file storage for small self-hosted deployments, see `SqliteDatabase`.
+    Sqlite,
+}
+
+impl DatabaseBackend {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "mongodb" | "mongo" => Some(Self::Mongo),
+            "memory" => Some(Self::Memory),
+            "sqlite" => Some(Self::Sqlite),
+            _ => None,
+        }
+    }
+
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        match source.var("DATABASE_BACKEND") {
+            Some(value) => Self::parse(&value).ok_or_else(|| {
+                format!(
+                    "Invalid DATABASE_BACKEND: {} (expected mongodb, memory or sqlite)",
+                    value
+                )
+            }),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// Changes to a user's level and badges computed from a snapshot of the user.
+///
+/// Badges are added rather than replaced, so awards granted concurrently are kept,
+/// and the write only succeeds while the stored level still equals `expected_level`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadgeUpdate {
+    pub expected_level: i32,
+    pub level: i32,
+    pub add_badges: Vec<String>,
+    /// New verified status, or `None` to leave it untouched. Not applied while verification is locked.
+    pub verified: Option<bool>,
+}
+
+#[async_trait]
+pub trait Database: Send + Sync {
+    /// Checks that the database is reachable. Backends without a server always are.
+    async fn ping(&self) -> Result<(), Error> {
+        Ok(())
+    }
+    async fn find_user(&self, user_id: &ObjectId) -> Result<Option<User>, Error>;
+    /// Applies a badge update unless the user changed since it was computed.
+    /// Returns `false` on conflict (or if the user no longer exists).
+    async fn apply_badge_update(
+        &self,
+        user_id: &ObjectId,
+        update: &BadgeUpdate,
+    ) -> Result<bool, Error>;
+    async fn get_user_recipes(&self, user_id: &ObjectId) -> Result<Vec<Recipe>, Error>;
+    /// Recipe count, like total and distinct active days/weeks of a user, computed without
+    /// loading the recipes themselves.
+    async fn get_user_stats(&self, user_id: &ObjectId) -> Result<UserStats, Error>;
+    /// Drops recipe stats and lists of a user cached from queries started before
+    /// `loaded_before`. Backends without a cache have nothing to drop.
+    fn expire_aggregates(&self, _user_id: &ObjectId, _loaded_before: DateTime<Utc>) {}
+    async fn add_badge_to_user(
+        &self,
+        user_id: &ObjectId,
+        badge: &str,
+    ) -> Result<Option<bool>, Error>;
+    /// Records a top recipe win and grants its badge. Returns `None` if the user does not exist;
+    /// a win for a period that is already recorded is reported as not newly awarded.
+    async fn record_top_recipe_award(
+        &self,
+        user_id: &ObjectId,
+        award: &TopRecipeAward,
+    ) -> Result<Option<AwardOutcome>, Error>;
+    /// Overrides the verified status of a user on behalf of the API key named `updated_by`.
+    /// Returns `false` if the user does not exist.
+    async fn set_verification(
+        &self,
+        user_id: &ObjectId,
+        verified: bool,
+        locked: bool,
+        reason: &str,
+        updated_by: &str,
+    ) -> Result<bool, Error>;
+    /// Holds badges for admin review. Merges into the user's pending review if one exists.
+    async fn hold_badges_for_review(&self, review: &BadgeReview) -> Result<(), Error>;
+    async fn get_pending_badge_reviews(&self) -> Result<Vec<BadgeReview>, Error>;
+    /// Every review of a user, oldest first, whatever its status.
+    async fn get_user_badge_reviews(&self, user_id: &ObjectId) -> Result<Vec<BadgeReview>, Error>;
+    /// Approves or rejects a pending review on behalf of the API key named `resolved_by`.
+    /// Returns `None` if no pending review has this id.
+    async fn resolve_badge_review(
+        &self,
+        review_id: &ObjectId,
+        status: ReviewStatus,
+        resolved_by: &str,
+    ) -> Result<Option<BadgeReview>, Error>;
+}
@@ -0,0 +1,476 @@
+use super::{BadgeUpdate, Database};
+use crate::error::Error;
+use crate::model::award::{AwardOutcome, TopRecipeAward};
+use crate::model::recipe::Recipe;
+use crate::model::review::{BadgeReview, ReviewStatus};
+use crate::model::stats::UserStats;
+use crate::model::user::User;
+use crate::service::indexes::{
+    IndexMode, IndexReport, IndexStatus, index_status, required_indexes,
+};
+use crate::service::schema::{FieldCoverage, SCHEMA_SAMPLE_SIZE, SchemaMapping};
+use async_trait::async_trait;
+use futures_util::stream::TryStreamExt;
+use mongodb::bson::Document;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::ErrorKind;
+use mongodb::options::ReturnDocument;
+use mongodb::{Client, IndexModel};
+use std::collections::BTreeSet;
+
+/// Server error code for a collection that does not exist.
+const NAMESPACE_NOT_FOUND: i32 = 26;
+
+pub struct MongoDatabase {
+    client: Client,
+    db_name: String,
+    schema: SchemaMapping,
+}
+
+impl MongoDatabase {
+    pub fn new(client: Client, db_name: String) -> Self {
+        Self {
+            client,
+            db_name,
+            schema: SchemaMapping::default(),
+        }
+    }
+
+    pub fn with_schema(mut self, schema: SchemaMapping) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Samples the user and recipe collections and reports how often each mapped field occurs.
+    /// Fails if a required recipe field is absent from every sampled recipe.
+    pub async fn validate_schema(&self) -> Result<Vec<FieldCoverage>, Error> {
+        let database = self.client.database(&self.db_name);
+        let sample = vec![mongodb::bson::doc! { "$sample": { "size": SCHEMA_SAMPLE_SIZE } }];
+
+        let recipes: Vec<Document> = database
+            .collection::<Document>(&self.schema.recipe_collection)
+            .aggregate(sample.clone())
+            .await?
+            .try_collect()
+            .await?;
+        let users: Vec<Document> = database
+            .collection::<Document>(&self.schema.user_collection)
+            .aggregate(sample)
+            .await?
+            .try_collect()
+            .await?;
+
+        let coverage = self.schema.check_samples(&recipes, &users);
+        if let Some(missing) = coverage
+            .iter()
+            .find(|field| field.required && field.is_missing())
+        {
+            return Err(Error::Validation(format!(
+                "Field {} is missing from all {} sampled {} documents, check the SCHEMA_* mapping",
+                missing.field, missing.sampled, missing.collection
+            )));
+        }
+        Ok(coverage)
+    }
+
+    /// Compares the indexes badge_forge relies on with the ones in the database and, in
+    /// `IndexMode::Create`, creates the missing ones. Drifted indexes are only reported.
+    pub async fn ensure_indexes(&self, mode: IndexMode) -> Result<IndexReport, Error> {
+        let mut report = IndexReport::default();
+        if mode == IndexMode::Off {
+            return Ok(report);
+        }
+
+        let specs = required_indexes(&self.schema);
+        let collections: BTreeSet<&str> =
+            specs.iter().map(|spec| spec.collection.as_str()).collect();
+
+        for collection_name in collections {
+            let collection = self
+                .client
+                .database(&self.db_name)
+                .collection::<Document>(collection_name);
+
+            let existing: Vec<IndexModel> = match collection.list_indexes().await {
+                Ok(cursor) => cursor.try_collect().await?,
+                // The collection does not exist yet, so it has no indexes
+                Err(e) if matches!(*e.kind, ErrorKind::Command(ref c) if c.code == NAMESPACE_NOT_FOUND) => {
+                    Vec::new()
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            for spec in specs
+                .iter()
+                .filter(|spec| spec.collection.as_str() == collection_name)
+            {
+                match index_status(spec, &existing) {
+                    IndexStatus::Present => report.present.push(spec.qualified_name()),
+                    IndexStatus::Drifted(reason) => {
+                        report.drifted.push((spec.qualified_name(), reason))
+                    }
+                    IndexStatus::Missing if mode == IndexMode::Check => {
+                        report.missing.push(spec.qualified_name())
+                    }
+                    IndexStatus::Missing => {
+                        collection.create_index(spec.to_model()).await?;
+                        report.created.push(spec.qualified_name());
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl Database for MongoDatabase {
+    async fn ping(&self) -> Result<(), Error> {
+        self.client
+            .database(&self.db_name)
+            .run_command(mongodb::bson::doc! { "ping": 1 })
+            .await?;
+        Ok(())
+    }
+
+    async fn find_user(&self, user_id: &ObjectId) -> Result<Option<User>, Error> {
+        let user_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<Document>(&self.schema.user_collection);
+        user_collection
+            .find_one(mongodb::bson::doc! { "_id": user_id })
+            .await?
+            .map(|document| self.schema.user_from_document(document))
+            .transpose()
+    }
+
+    async fn apply_badge_update(
+        &self,
+        user_id: &ObjectId,
+        update: &BadgeUpdate,
+    ) -> Result<bool, Error> {
+        let user_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<Document>(&self.schema.user_collection);
+        let fields = &self.schema.user;
+
+        // Users that were never computed have no level field yet
+        let mut filter = if update.expected_level == 0 {
+            mongodb::bson::doc! { "_id": user_id, &fields.level: { "$in": [0, null] } }
+        } else {
+            mongodb::bson::doc! { "_id": user_id, &fields.level: update.expected_level }
+        };
+        let mut set = mongodb::bson::doc! { &fields.level: update.level };
+        if let Some(verified) = update.verified {
+            filter.insert(
+                &fields.verification_locked,
+                mongodb::bson::doc! { "$ne": true },
+            );
+            set.insert(&fields.verified, verified);
+        }
+
+        user_collection
+            .update_one(
+                filter,
+                mongodb::bson::doc! {
+                    "$set": set,
+                    "$addToSet": { &fields.badges: { "$each": &update.add_badges } }
+                },
+            )
+            .await
+            .map(|result| result.matched_count > 0)
+            .map_err(Error::from)
+    }
+
+    async fn get_user_recipes(&self, user_id: &ObjectId) -> Result<Vec<Recipe>, Error> {
+        let recipe_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<Document>(&self.schema.recipe_collection);
+        let mut cursor = recipe_collection
+            .find(mongodb::bson::doc! { &self.schema.recipe.user_id: user_id })
+            .projection(self.schema.recipe_projection())
+            .await
+            .map_err(Error::from)?;
+
+        let mut recipes = Vec::new();
+        while let Some(document) = cursor.try_next().await.map_err(Error::from)? {
+            recipes.push(self.schema.recipe_from_document(document)?);
+        }
+        Ok(recipes)
+    }
+
+    async fn get_user_stats(&self, user_id: &ObjectId) -> Result<UserStats, Error> {
+        let recipe_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<Document>(&self.schema.recipe_collection);
+        let num_likes = format!("${}", self.schema.recipe.num_likes);
+        let created_at = format!("${}", self.schema.recipe.created_at);
+        let pipeline = vec![
+            mongodb::bson::doc! { "$match": { &self.schema.recipe.user_id: user_id } },
+            mongodb::bson::doc! {
+                "$project": {
+                    "_id": 0,
+                    "numLikes": { "$max": [{ "$ifNull": [num_likes, 0] }, 0] },
+                    "createdAt": {
+                        "$convert": {
+                            "input": created_at,
+                            "to": "date",
+                            "onError": null,
+                            "onNull": null
+                        }
+                    }
+                }
+            },
+            // Recipes without a valid creation date cannot be read as a `Recipe` either, and
+            // would otherwise count as an extra active day and week
+            mongodb::bson::doc! { "$match": { "createdAt": { "$type": "date" } } },
+            mongodb::bson::doc! {
+                "$group": {
+                    "_id": null,
+                    "numRecipes": { "$sum": 1 },
+                    "totalLikes": { "$sum": "$numLikes" },
+                    "days": {
+                        "$addToSet": {
+                            "$dateToString": { "format": "%Y-%m-%d", "date": "$createdAt" }
+                        }
+                    },
+                    "weeks": {
+                        "$addToSet": {
+                            "year": { "$isoWeekYear": "$createdAt" },
+                            "week": { "$isoWeek": "$createdAt" }
+                        }
+                    }
+                }
+            },
+            mongodb::bson::doc! {
+                "$project": {
+                    "numRecipes": 1,
+                    "totalLikes": 1,
+                    "activeDays": { "$size": "$days" },
+                    "activeWeeks": { "$size": "$weeks" }
+                }
+            },
+        ];
+
+        let mut cursor = recipe_collection
+            .aggregate(pipeline)
+            .await
+            .map_err(Error::from)?;
+
+        let Some(result) = cursor.try_next().await.map_err(Error::from)? else {
+            return Ok(UserStats::default());
+        };
+
+        let count = |field: &str| -> u32 {
+            match result.get(field) {
+                Some(mongodb::bson::Bson::Int32(v)) => (*v).max(0) as u32,
+                Some(mongodb::bson::Bson::Int64(v)) => {
+                    u32::try_from((*v).max(0)).unwrap_or(u32::MAX)
+                }
+                // Float casts saturate instead of wrapping
+                Some(mongodb::bson::Bson::Double(v)) => v.max(0.0) as u32,
+                _ => 0,
+            }
+        };
+
+        Ok(UserStats {
+            num_recipes: count("numRecipes"),
+            total_likes: count("totalLikes"),
+            active_days: count("activeDays"),
+            active_weeks: count("activeWeeks"),
+        })
+    }
+
+    async fn add_badge_to_user(
+        &self,
+        user_id: &ObjectId,
+        badge: &str,
+    ) -> Result<Option<bool>, Error> {
+        let user_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<Document>(&self.schema.user_collection);
+        let result = user_collection
+            .update_one(
+                mongodb::bson::doc! { "_id": user_id },
+                mongodb::bson::doc! { "$addToSet": { &self.schema.user.badges: badge } },
+            )
+            .await
+            .map_err(Error::from)?;
+
+        if result.matched_count == 0 {
+            Ok(None)
+        } else if result.modified_count == 0 {
+            Ok(Some(false))
+        } else {
+            Ok(Some(true))
+        }
+    }
+
+    async fn record_top_recipe_award(
+        &self,
+        user_id: &ObjectId,
+        award: &TopRecipeAward,
+    ) -> Result<Option<AwardOutcome>, Error> {
+        let user_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<Document>(&self.schema.user_collection);
+        let fields = &self.schema.user;
+        let award_bson = mongodb::bson::to_bson(award).map_err(Error::from)?;
+
+        let updated = user_collection
+            .find_one_and_update(
+                mongodb::bson::doc! {
+                    "_id": user_id,
+                    &fields.top_recipe_awards: {
+                        "$not": { "$elemMatch": { "badge": &award.badge, "period": &award.period } }
+                    }
+                },
+                mongodb::bson::doc! {
+                    "$push": { &fields.top_recipe_awards: award_bson },
+                    "$addToSet": { &fields.badges: &award.badge }
+                },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(Error::from)?;
+
+        if let Some(document) = updated {
+            let user = self.schema.user_from_document(document)?;
+            return Ok(Some(AwardOutcome {
+                newly_awarded: true,
+                win_count: user.top_recipe_wins(&award.badge),
+            }));
+        }
+
+        // Either the user does not exist or the period was already recorded
+        Ok(self.find_user(user_id).await?.map(|user| AwardOutcome {
+            newly_awarded: false,
+            win_count: user.top_recipe_wins(&award.badge),
+        }))
+    }
+
+    async fn set_verification(
+        &self,
+        user_id: &ObjectId,
+        verified: bool,
+        locked: bool,
+        reason: &str,
+        updated_by: &str,
+    ) -> Result<bool, Error> {
+        let user_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<Document>(&self.schema.user_collection);
+        let fields = &self.schema.user;
+        user_collection
+            .update_one(
+                mongodb::bson::doc! { "_id": user_id },
+                mongodb::bson::doc! {
+                    "$set": {
+                        &fields.verified: verified,
+                        &fields.verification_locked: locked,
+                        &fields.verification_reason: reason,
+                        &fields.verification_updated_by: updated_by
+                    }
+                },
+            )
+            .await
+            .map(|result| result.matched_count > 0)
+            .map_err(Error::from)
+    }
+
+    async fn hold_badges_for_review(&self, review: &BadgeReview) -> Result<(), Error> {
+        let review_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<BadgeReview>(&self.schema.review_collection);
+        let signals = mongodb::bson::to_bson(&review.signals).map_err(Error::from)?;
+        let created_at = mongodb::bson::DateTime::from_millis(review.created_at.timestamp_millis());
+
+        review_collection
+            .update_one(
+                mongodb::bson::doc! { "userId": review.user_id, "status": "pending" },
+                mongodb::bson::doc! {
+                    "$addToSet": { "badges": { "$each": &review.badges } },
+                    "$max": { "verify": review.verify },
+                    "$set": { "signals": signals, "score": review.score },
+                    "$setOnInsert": { "_id": review._id, "createdAt": created_at }
+                },
+            )
+            .upsert(true)
+            .await
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    async fn get_pending_badge_reviews(&self) -> Result<Vec<BadgeReview>, Error> {
+        let review_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<BadgeReview>(&self.schema.review_collection);
+        let mut cursor = review_collection
+            .find(mongodb::bson::doc! { "status": "pending" })
+            .sort(mongodb::bson::doc! { "createdAt": 1 })
+            .await
+            .map_err(Error::from)?;
+
+        let mut reviews = Vec::new();
+        while let Some(review) = cursor.try_next().await.map_err(Error::from)? {
+            reviews.push(review);
+        }
+        Ok(reviews)
+    }
+
+    async fn get_user_badge_reviews(&self, user_id: &ObjectId) -> Result<Vec<BadgeReview>, Error> {
+        let review_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<BadgeReview>(&self.schema.review_collection);
+        let mut cursor = review_collection
+            .find(mongodb::bson::doc! { "userId": user_id })
+            .sort(mongodb::bson::doc! { "createdAt": 1 })
+            .await
+            .map_err(Error::from)?;
+
+        let mut reviews = Vec::new();
+        while let Some(review) = cursor.try_next().await.map_err(Error::from)? {
+            reviews.push(review);
+        }
+        Ok(reviews)
+    }
+
+    async fn resolve_badge_review(
+        &self,
+        review_id: &ObjectId,
+        status: ReviewStatus,
+        resolved_by: &str,
+    ) -> Result<Option<BadgeReview>, Error> {
+        let review_collection = self
+            .client
+            .database(&self.db_name)
+            .collection::<BadgeReview>(&self.schema.review_collection);
+        let status = mongodb::bson::to_bson(&status).map_err(Error::from)?;
+
+        review_collection
+            .find_one_and_update(
+                mongodb::bson::doc! { "_id": review_id, "status": "pending" },
+                mongodb::bson::doc! {
+                    "$set": {
+                        "status": status,
+                        "resolvedAt": mongodb::bson::DateTime::now(),
+                        "resolvedBy": resolved_by
+                    }
+                },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(Error::from)
+    }
+}
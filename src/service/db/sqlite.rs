@@ -0,0 +1,699 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{BadgeUpdate, Database, MemorySnapshot};
+use crate::error::Error;
+use crate::model::award::{AwardOutcome, TopRecipeAward};
+use crate::model::recipe::Recipe;
+use crate::model::review::{AbuseSignal, BadgeReview, ReviewStatus};
+use crate::model::stats::UserStats;
+use crate::model::user::User;
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use mongodb::bson::oid::ObjectId;
+use rusqlite::{Connection, OptionalExtension, Row, Transaction, params};
+
+/// Schema migrations, applied in order. `PRAGMA user_version` stores how many ran.
+/// Never edit a released migration; append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: users, badges, recipes, award history and badge reviews
+    "
+    CREATE TABLE users (
+        id TEXT PRIMARY KEY,
+        name TEXT,
+        email TEXT,
+        level INTEGER NOT NULL DEFAULT 0,
+        verified INTEGER,
+        verification_locked INTEGER,
+        verification_reason TEXT,
+        created_at TEXT
+    );
+
+    CREATE TABLE user_badges (
+        user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+        badge TEXT NOT NULL,
+        PRIMARY KEY (user_id, badge)
+    );
+
+    CREATE TABLE recipes (
+        id TEXT PRIMARY KEY,
+        user_id TEXT NOT NULL,
+        num_likes INTEGER NOT NULL DEFAULT 0,
+        created_at TEXT NOT NULL
+    );
+    CREATE INDEX recipes_user_id ON recipes(user_id);
+
+    CREATE TABLE top_recipe_awards (
+        user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+        badge TEXT NOT NULL,
+        period TEXT NOT NULL,
+        recipe_id TEXT,
+        awarded_at TEXT NOT NULL,
+        PRIMARY KEY (user_id, badge, period)
+    );
+
+    CREATE TABLE badge_reviews (
+        id TEXT PRIMARY KEY,
+        user_id TEXT NOT NULL,
+        badges TEXT NOT NULL,
+        verify INTEGER NOT NULL,
+        signals TEXT NOT NULL,
+        score INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        resolved_at TEXT
+    );
+    CREATE UNIQUE INDEX badge_reviews_pending_user ON badge_reviews(user_id)
+        WHERE status = 'pending';
+    CREATE INDEX badge_reviews_status_created ON badge_reviews(status, created_at);
+    ",
+    // 2: reviews of one user, checked on every badge update
+    "
+    CREATE INDEX badge_reviews_user_created ON badge_reviews(user_id, created_at);
+    ",
+    // 3: API key names on admin decisions
+    "
+    ALTER TABLE users ADD COLUMN verification_updated_by TEXT;
+    ALTER TABLE badge_reviews ADD COLUMN resolved_by TEXT;
+    ",
+];
+
+/// `Database` stored in a SQLite file, for small self-hosted deployments without MongoDB.
+///
+/// Ids are stored as ObjectId hex strings and dates as RFC 3339 UTC strings with
+/// millisecond precision, so they sort chronologically.
+pub struct SqliteDatabase {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteDatabase {
+    /// Opens (or creates) the database file and applies pending migrations.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self, Error> {
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Number of migrations applied to the database.
+    pub fn schema_version(&self) -> Result<usize, Error> {
+        let conn = self.lock()?;
+        Ok(conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))? as usize)
+    }
+
+    /// Whether the database holds no users yet, e.g. to decide whether to seed it.
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        let conn = self.lock()?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))?;
+        Ok(count == 0)
+    }
+
+    /// Inserts or replaces the users, recipes and reviews of a snapshot.
+    pub fn import(&self, snapshot: &MemorySnapshot) -> Result<(), Error> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+        for user in &snapshot.users {
+            insert_user(&tx, user)?;
+        }
+        for recipe in &snapshot.recipes {
+            insert_recipe(&tx, recipe)?;
+        }
+        for review in &snapshot.reviews {
+            insert_review(&tx, review)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, Error> {
+        self.conn
+            .lock()
+            .map_err(|_| Error::Permanent("SQLite connection lock poisoned".to_string()))
+    }
+
+    /// Runs `f` on the connection in the blocking thread pool.
+    async fn call<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T, Error> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|_| Error::Permanent("SQLite connection lock poisoned".to_string()))?;
+            f(&mut conn)
+        })
+        .await
+        .map_err(|e| Error::Permanent(format!("SQLite task failed: {}", e)))?
+    }
+}
+
+fn migrate(conn: &mut Connection) -> Result<(), Error> {
+    let applied = conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))?;
+    if applied as usize > MIGRATIONS.len() {
+        return Err(Error::Permanent(format!(
+            "SQLite schema version {} is newer than this build supports ({})",
+            applied,
+            MIGRATIONS.len()
+        )));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(applied as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", (index + 1) as i64)?;
+        tx.commit()?;
+        tracing::info!("Applied SQLite migration {}", index + 1);
+    }
+    Ok(())
+}
+
+fn format_time(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_time(value: &str) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, e.into())
+        })
+}
+
+fn parse_id(value: &str) -> rusqlite::Result<ObjectId> {
+    ObjectId::parse_str(value).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, e.into())
+    })
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(value: &str) -> rusqlite::Result<T> {
+    serde_json::from_str(value).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, e.into())
+    })
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, Error> {
+    serde_json::to_string(value)
+        .map_err(|e| Error::Permanent(format!("Serialization error: {}", e)))
+}
+
+fn status_name(status: ReviewStatus) -> &'static str {
+    match status {
+        ReviewStatus::Pending => "pending",
+        ReviewStatus::Approved => "approved",
+        ReviewStatus::Rejected => "rejected",
+    }
+}
+
+fn parse_status(value: &str) -> rusqlite::Result<ReviewStatus> {
+    match value {
+        "pending" => Ok(ReviewStatus::Pending),
+        "approved" => Ok(ReviewStatus::Approved),
+        "rejected" => Ok(ReviewStatus::Rejected),
+        other => Err(rusqlite::Error::FromSqlConversionFailure(
+            0,
+            rusqlite::types::Type::Text,
+            format!("Unknown review status: {}", other).into(),
+        )),
+    }
+}
+
+fn insert_user(tx: &Transaction, user: &User) -> Result<(), Error> {
+    let id = user._id.to_hex();
+    tx.execute(
+        "INSERT OR REPLACE INTO users
+            (id, name, email, level, verified, verification_locked, verification_reason, created_at,
+             verification_updated_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            id,
+            user.name,
+            user.email,
+            user.level,
+            user.verified,
+            user.verification_locked,
+            user.verification_reason,
+            user.created_at.as_ref().map(format_time),
+            user.verification_updated_by,
+        ],
+    )?;
+    tx.execute("DELETE FROM user_badges WHERE user_id = ?1", params![id])?;
+    for badge in &user.badges {
+        tx.execute(
+            "INSERT OR IGNORE INTO user_badges (user_id, badge) VALUES (?1, ?2)",
+            params![id, badge],
+        )?;
+    }
+    tx.execute(
+        "DELETE FROM top_recipe_awards WHERE user_id = ?1",
+        params![id],
+    )?;
+    for award in &user.top_recipe_awards {
+        insert_award(tx, &user._id, award)?;
+    }
+    Ok(())
+}
+
+fn insert_recipe(tx: &Transaction, recipe: &Recipe) -> Result<(), Error> {
+    tx.execute(
+        "INSERT OR REPLACE INTO recipes (id, user_id, num_likes, created_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            recipe._id.to_hex(),
+            recipe.user_id.to_hex(),
+            recipe.num_likes,
+            format_time(&recipe.created_at),
+        ],
+    )?;
+    Ok(())
+}
+
+fn insert_review(tx: &Transaction, review: &BadgeReview) -> Result<(), Error> {
+    tx.execute(
+        "INSERT OR REPLACE INTO badge_reviews
+            (id, user_id, badges, verify, signals, score, status, created_at, resolved_at,
+             resolved_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            review._id.to_hex(),
+            review.user_id.to_hex(),
+            to_json(&review.badges)?,
+            review.verify,
+            to_json(&review.signals)?,
+            review.score,
+            status_name(review.status),
+            format_time(&review.created_at),
+            review.resolved_at.as_ref().map(format_time),
+            review.resolved_by,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Returns `false` if the award for this badge and period was already recorded.
+fn insert_award(
+    tx: &Transaction,
+    user_id: &ObjectId,
+    award: &TopRecipeAward,
+) -> Result<bool, Error> {
+    let inserted = tx.execute(
+        "INSERT OR IGNORE INTO top_recipe_awards (user_id, badge, period, recipe_id, awarded_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            user_id.to_hex(),
+            award.badge,
+            award.period,
+            award.recipe_id.map(|id| id.to_hex()),
+            format_time(&award.awarded_at),
+        ],
+    )?;
+    Ok(inserted > 0)
+}
+
+fn add_badges(tx: &Transaction, user_id: &ObjectId, badges: &[String]) -> Result<usize, Error> {
+    let mut added = 0;
+    for badge in badges {
+        added += tx.execute(
+            "INSERT OR IGNORE INTO user_badges (user_id, badge) VALUES (?1, ?2)",
+            params![user_id.to_hex(), badge],
+        )?;
+    }
+    Ok(added)
+}
+
+fn load_user(conn: &Connection, user_id: &ObjectId) -> Result<Option<User>, Error> {
+    let id = user_id.to_hex();
+    let user = conn
+        .query_row(
+            "SELECT name, email, level, verified, verification_locked, verification_reason,
+                    created_at, verification_updated_by
+             FROM users WHERE id = ?1",
+            params![id],
+            |row| {
+                let created_at: Option<String> = row.get(6)?;
+                Ok(User {
+                    _id: *user_id,
+                    name: row.get(0)?,
+                    email: row.get(1)?,
+                    level: row.get(2)?,
+                    badges: Vec::new(),
+                    verified: row.get(3)?,
+                    top_recipe_awards: Vec::new(),
+                    created_at: created_at.as_deref().map(parse_time).transpose()?,
+                    verification_locked: row.get(4)?,
+                    verification_reason: row.get(5)?,
+                    verification_updated_by: row.get(7)?,
+                })
+            },
+        )
+        .optional()?;
+    let Some(mut user) = user else {
+        return Ok(None);
+    };
+
+    // Badges and awards keep the order they were granted in
+    let mut badges =
+        conn.prepare("SELECT badge FROM user_badges WHERE user_id = ?1 ORDER BY rowid")?;
+    user.badges = badges
+        .query_map(params![id], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut awards = conn.prepare(
+        "SELECT badge, period, recipe_id, awarded_at FROM top_recipe_awards
+         WHERE user_id = ?1 ORDER BY rowid",
+    )?;
+    user.top_recipe_awards = awards
+        .query_map(params![id], |row| {
+            let recipe_id: Option<String> = row.get(2)?;
+            let awarded_at: String = row.get(3)?;
+            Ok(TopRecipeAward {
+                badge: row.get(0)?,
+                period: row.get(1)?,
+                recipe_id: recipe_id.as_deref().map(parse_id).transpose()?,
+                awarded_at: parse_time(&awarded_at)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+
+    Ok(Some(user))
+}
+
+fn review_from_row(row: &Row) -> rusqlite::Result<BadgeReview> {
+    let id: String = row.get(0)?;
+    let user_id: String = row.get(1)?;
+    let badges: String = row.get(2)?;
+    let signals: String = row.get(4)?;
+    let status: String = row.get(6)?;
+    let created_at: String = row.get(7)?;
+    let resolved_at: Option<String> = row.get(8)?;
+    Ok(BadgeReview {
+        _id: parse_id(&id)?,
+        user_id: parse_id(&user_id)?,
+        badges: from_json(&badges)?,
+        verify: row.get(3)?,
+        signals: from_json::<Vec<AbuseSignal>>(&signals)?,
+        score: row.get(5)?,
+        status: parse_status(&status)?,
+        created_at: parse_time(&created_at)?,
+        resolved_at: resolved_at.as_deref().map(parse_time).transpose()?,
+        resolved_by: row.get(9)?,
+    })
+}
+
+const REVIEW_COLUMNS: &str =
+    "id, user_id, badges, verify, signals, score, status, created_at, resolved_at, resolved_by";
+
+#[async_trait]
+impl Database for SqliteDatabase {
+    async fn ping(&self) -> Result<(), Error> {
+        self.call(|conn| Ok(conn.query_row("SELECT 1", [], |_| Ok(()))?))
+            .await
+    }
+
+    async fn find_user(&self, user_id: &ObjectId) -> Result<Option<User>, Error> {
+        let user_id = *user_id;
+        self.call(move |conn| load_user(conn, &user_id)).await
+    }
+
+    async fn apply_badge_update(
+        &self,
+        user_id: &ObjectId,
+        update: &BadgeUpdate,
+    ) -> Result<bool, Error> {
+        let user_id = *user_id;
+        let update = update.clone();
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            let updated = tx.execute(
+                "UPDATE users SET level = ?2, verified = COALESCE(?3, verified)
+                 WHERE id = ?1 AND level = ?4
+                   AND (?3 IS NULL OR COALESCE(verification_locked, 0) = 0)",
+                params![
+                    user_id.to_hex(),
+                    update.level,
+                    update.verified,
+                    update.expected_level
+                ],
+            )?;
+            if updated == 0 {
+                return Ok(false);
+            }
+            add_badges(&tx, &user_id, &update.add_badges)?;
+            tx.commit()?;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn get_user_recipes(&self, user_id: &ObjectId) -> Result<Vec<Recipe>, Error> {
+        let user_id = *user_id;
+        self.call(move |conn| {
+            let mut statement = conn.prepare(
+                "SELECT id, num_likes, created_at FROM recipes WHERE user_id = ?1
+                 ORDER BY created_at",
+            )?;
+            let recipes = statement
+                .query_map(params![user_id.to_hex()], |row| {
+                    let id: String = row.get(0)?;
+                    let created_at: String = row.get(2)?;
+                    Ok(Recipe {
+                        _id: parse_id(&id)?,
+                        user_id,
+                        num_likes: row.get(1)?,
+                        created_at: parse_time(&created_at)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(recipes)
+        })
+        .await
+    }
+
+    async fn get_user_stats(&self, user_id: &ObjectId) -> Result<UserStats, Error> {
+        let user_id = *user_id;
+        self.call(move |conn| {
+            let stats = conn.query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(MAX(num_likes, 0)), 0),
+                        COUNT(DISTINCT date(created_at)),
+                        COUNT(DISTINCT strftime('%G-%V', created_at))
+                 FROM recipes WHERE user_id = ?1",
+                params![user_id.to_hex()],
+                |row| {
+                    let count = |index: usize| -> rusqlite::Result<u32> {
+                        Ok(row.get::<_, i64>(index)?.clamp(0, u32::MAX as i64) as u32)
+                    };
+                    Ok(UserStats {
+                        num_recipes: count(0)?,
+                        total_likes: count(1)?,
+                        active_days: count(2)?,
+                        active_weeks: count(3)?,
+                    })
+                },
+            )?;
+            Ok(stats)
+        })
+        .await
+    }
+
+    async fn add_badge_to_user(
+        &self,
+        user_id: &ObjectId,
+        badge: &str,
+    ) -> Result<Option<bool>, Error> {
+        let user_id = *user_id;
+        let badge = badge.to_string();
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            let exists = tx
+                .query_row(
+                    "SELECT 1 FROM users WHERE id = ?1",
+                    params![user_id.to_hex()],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some();
+            if !exists {
+                return Ok(None);
+            }
+            let added = add_badges(&tx, &user_id, std::slice::from_ref(&badge))? > 0;
+            tx.commit()?;
+            Ok(Some(added))
+        })
+        .await
+    }
+
+    async fn record_top_recipe_award(
+        &self,
+        user_id: &ObjectId,
+        award: &TopRecipeAward,
+    ) -> Result<Option<AwardOutcome>, Error> {
+        let user_id = *user_id;
+        let award = award.clone();
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            let exists = tx
+                .query_row(
+                    "SELECT 1 FROM users WHERE id = ?1",
+                    params![user_id.to_hex()],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some();
+            if !exists {
+                return Ok(None);
+            }
+
+            let newly_awarded = insert_award(&tx, &user_id, &award)?;
+            if newly_awarded {
+                add_badges(&tx, &user_id, std::slice::from_ref(&award.badge))?;
+            }
+            let win_count: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM top_recipe_awards WHERE user_id = ?1 AND badge = ?2",
+                params![user_id.to_hex(), award.badge],
+                |row| row.get(0),
+            )?;
+            tx.commit()?;
+
+            Ok(Some(AwardOutcome {
+                newly_awarded,
+                win_count: win_count as u32,
+            }))
+        })
+        .await
+    }
+
+    async fn set_verification(
+        &self,
+        user_id: &ObjectId,
+        verified: bool,
+        locked: bool,
+        reason: &str,
+        updated_by: &str,
+    ) -> Result<bool, Error> {
+        let user_id = *user_id;
+        let reason = reason.to_string();
+        let updated_by = updated_by.to_string();
+        self.call(move |conn| {
+            let updated = conn.execute(
+                "UPDATE users SET verified = ?2, verification_locked = ?3, verification_reason = ?4,
+                    verification_updated_by = ?5
+                 WHERE id = ?1",
+                params![user_id.to_hex(), verified, locked, reason, updated_by],
+            )?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn hold_badges_for_review(&self, review: &BadgeReview) -> Result<(), Error> {
+        let review = review.clone();
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            let pending = tx
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM badge_reviews WHERE user_id = ?1 AND status = 'pending'",
+                        REVIEW_COLUMNS
+                    ),
+                    params![review.user_id.to_hex()],
+                    review_from_row,
+                )
+                .optional()?;
+
+            let merged = match pending {
+                Some(mut pending) => {
+                    for badge in &review.badges {
+                        if !pending.badges.contains(badge) {
+                            pending.badges.push(badge.clone());
+                        }
+                    }
+                    pending.verify |= review.verify;
+                    pending.signals = review.signals;
+                    pending.score = review.score;
+                    pending
+                }
+                None => review,
+            };
+            insert_review(&tx, &merged)?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_pending_badge_reviews(&self) -> Result<Vec<BadgeReview>, Error> {
+        self.call(|conn| {
+            let mut statement = conn.prepare(&format!(
+                "SELECT {} FROM badge_reviews WHERE status = 'pending' ORDER BY created_at",
+                REVIEW_COLUMNS
+            ))?;
+            let reviews = statement
+                .query_map([], review_from_row)?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(reviews)
+        })
+        .await
+    }
+
+    async fn get_user_badge_reviews(&self, user_id: &ObjectId) -> Result<Vec<BadgeReview>, Error> {
+        let user_id = *user_id;
+        self.call(move |conn| {
+            let mut statement = conn.prepare(&format!(
+                "SELECT {} FROM badge_reviews WHERE user_id = ?1 ORDER BY created_at",
+                REVIEW_COLUMNS
+            ))?;
+            let reviews = statement
+                .query_map(params![user_id.to_hex()], review_from_row)?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(reviews)
+        })
+        .await
+    }
+
+    async fn resolve_badge_review(
+        &self,
+        review_id: &ObjectId,
+        status: ReviewStatus,
+        resolved_by: &str,
+    ) -> Result<Option<BadgeReview>, Error> {
+        let review_id = *review_id;
+        let resolved_by = resolved_by.to_string();
+        self.call(move |conn| {
+            let tx = conn.transaction()?;
+            let updated = tx.execute(
+                "UPDATE badge_reviews SET status = ?2, resolved_at = ?3, resolved_by = ?4
+                 WHERE id = ?1 AND status = 'pending'",
+                params![
+                    review_id.to_hex(),
+                    status_name(status),
+                    format_time(&Utc::now()),
+                    resolved_by
+                ],
+            )?;
+            if updated == 0 {
+                return Ok(None);
+            }
+            let review = tx.query_row(
+                &format!("SELECT {} FROM badge_reviews WHERE id = ?1", REVIEW_COLUMNS),
+                params![review_id.to_hex()],
+                review_from_row,
+            )?;
+            tx.commit()?;
+            Ok(Some(review))
+        })
+        .await
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::queue::BadgeUpdateQueue;
+use crate::service::connectivity::Connectivity;
+use crate::service::heartbeat::Heartbeat;
+use crate::service::notifier::Notifier;
+
+/// A processor that has not beaten for this long is considered dead.
+pub const PROCESSOR_STALE_AFTER_SECS: i64 = 60;
+/// Share of the queue capacity above which the service stops reporting ready.
+pub const QUEUE_SATURATION_PERCENT: usize = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Ok,
+    /// Not working, but the service can still do its job; does not fail readiness.
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentHealth {
+    pub status: ComponentStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ComponentHealth {
+    fn ok() -> Self {
+        Self {
+            status: ComponentStatus::Ok,
+            message: None,
+        }
+    }
+
+    fn with(status: ComponentStatus, message: String) -> Self {
+        Self {
+            status,
+            message: Some(message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub components: BTreeMap<&'static str, ComponentHealth>,
+}
+
+/// Checks the dependencies the service needs to accept and process badge updates.
+///
+/// The notifier only warns: a notifier outage loses notifications, not badges, and taking
+/// the service out of rotation would not bring it back.
+pub async fn check_readiness(
+    connectivity: &Connectivity,
+    processor: &Heartbeat,
+    queue: &dyn BadgeUpdateQueue,
+    notifier: &dyn Notifier,
+    now: DateTime<Utc>,
+) -> ReadinessReport {
+    let mut components = BTreeMap::new();
+
+    components.insert(
+        "database",
+        if connectivity.is_ready() {
+            ComponentHealth::ok()
+        } else {
+            ComponentHealth::with(ComponentStatus::Fail, "Database unreachable".to_string())
+        },
+    );
+
+    let stale_after = chrono::Duration::seconds(PROCESSOR_STALE_AFTER_SECS);
+    components.insert(
+        "processor",
+        if processor.is_alive(stale_after, now) {
+            ComponentHealth::ok()
+        } else {
+            let message = match processor.last_beat() {
+                Some(last) => format!("No heartbeat since {}", last.to_rfc3339()),
+                None => "Processor has not started".to_string(),
+            };
+            ComponentHealth::with(ComponentStatus::Fail, message)
+        },
+    );
+
+    let pending = queue.get_pending_requests().await.len();
+    components.insert(
+        "queue",
+        match queue.capacity() {
+            Some(capacity) if pending * 100 >= capacity * QUEUE_SATURATION_PERCENT => {
+                ComponentHealth::with(
+                    ComponentStatus::Fail,
+                    format!("{} of {} slots in use", pending, capacity),
+                )
+            }
+            _ => ComponentHealth::ok(),
+        },
+    );
+
+    components.insert(
+        "notifier",
+        match notifier.check().await {
+            Ok(()) => ComponentHealth::ok(),
+            Err(message) => ComponentHealth::with(ComponentStatus::Warn, message),
+        },
+    );
+
+    ReadinessReport {
+        ready: components
+            .values()
+            .all(|component| component.status != ComponentStatus::Fail),
+        components,
+    }
+}
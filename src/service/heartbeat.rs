@@ -0,0 +1,34 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use chrono::{DateTime, Utc};
+
+/// Last sign of life of a background task. Clones observe the same heartbeat.
+#[derive(Debug, Clone, Default)]
+pub struct Heartbeat {
+    /// Unix time in milliseconds of the last beat, 0 before the first one.
+    last_beat: Arc<AtomicI64>,
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn beat(&self) {
+        self.last_beat
+            .store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+
+    pub fn last_beat(&self) -> Option<DateTime<Utc>> {
+        match self.last_beat.load(Ordering::Relaxed) {
+            0 => None,
+            millis => DateTime::from_timestamp_millis(millis),
+        }
+    }
+
+    /// Whether the task beat within `max_age` of `now`.
+    pub fn is_alive(&self, max_age: chrono::Duration, now: DateTime<Utc>) -> bool {
+        self.last_beat().is_some_and(|last| now - last <= max_age)
+    }
+}
@@ -0,0 +1,188 @@
+use mongodb::IndexModel;
+use mongodb::bson::{Bson, Document, doc};
+use mongodb::options::IndexOptions;
+
+use crate::config::ConfigSource;
+use crate::service::schema::SchemaMapping;
+
+/// What `MongoDatabase::ensure_indexes` does at startup, read from `MONGODB_INDEXES`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexMode {
+    /// Create missing indexes and report drift.
+    #[default]
+    Create,
+    /// Only report missing and drifted indexes, for deployments without index privileges.
+    Check,
+    /// Skip index management entirely.
+    Off,
+}
+
+impl IndexMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "create" => Some(Self::Create),
+            "check" => Some(Self::Check),
+            "off" => Some(Self::Off),
+            _ => None,
+        }
+    }
+
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        match source.var("MONGODB_INDEXES") {
+            Some(value) => Self::parse(&value).ok_or_else(|| {
+                format!(
+                    "Invalid MONGODB_INDEXES: {} (expected create, check or off)",
+                    value
+                )
+            }),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// An index badge_forge relies on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexSpec {
+    pub collection: String,
+    pub name: &'static str,
+    pub keys: Document,
+    pub unique: bool,
+    pub partial_filter: Option<Document>,
+}
+
+impl IndexSpec {
+    pub fn to_model(&self) -> IndexModel {
+        let options = IndexOptions::builder()
+            .name(self.name.to_string())
+            .unique(self.unique.then_some(true))
+            .partial_filter_expression(self.partial_filter.clone())
+            .build();
+        IndexModel::builder()
+            .keys(self.keys.clone())
+            .options(options)
+            .build()
+    }
+
+    /// `Collection.name`, as used in logs and reports.
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.collection, self.name)
+    }
+}
+
+/// The indexes badge_forge needs, grouped by collection in declaration order.
+pub fn required_indexes(schema: &SchemaMapping) -> Vec<IndexSpec> {
+    vec![
+        // Every badge update loads the stats and recipes of one user
+        IndexSpec {
+            collection: schema.recipe_collection.clone(),
+            name: "badge_forge_user",
+            keys: doc! { &schema.recipe.user_id: 1 },
+            unique: false,
+            partial_filter: None,
+        },
+        // At most one pending review per user, which `hold_badges_for_review` upserts into
+        IndexSpec {
+            collection: schema.review_collection.clone(),
+            name: "badge_forge_pending_user",
+            keys: doc! { "userId": 1, "status": 1 },
+            unique: true,
+            partial_filter: Some(doc! { "status": "pending" }),
+        },
+        IndexSpec {
+            collection: schema.review_collection.clone(),
+            name: "badge_forge_status_created",
+            keys: doc! { "status": 1, "createdAt": 1 },
+            unique: false,
+            partial_filter: None,
+        },
+        // Every badge update checks the reviews of one user
+        IndexSpec {
+            collection: schema.review_collection.clone(),
+            name: "badge_forge_user_created",
+            keys: doc! { "userId": 1, "createdAt": 1 },
+            unique: false,
+            partial_filter: None,
+        },
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexStatus {
+    /// An index with the declared keys and options exists, under any name.
+    Present,
+    Missing,
+    /// An index with the same name or keys exists but differs from the declaration.
+    Drifted(String),
+}
+
+/// Compares a declared index with the indexes that exist on its collection. Indexes are
+/// matched by keys and options rather than by name, so an equivalent index created by hand
+/// counts as present.
+pub fn index_status(spec: &IndexSpec, existing: &[IndexModel]) -> IndexStatus {
+    let differences = |model: &IndexModel| {
+        let mut differences = Vec::new();
+        if !same_keys(&model.keys, &spec.keys) {
+            differences.push(format!("keys are {} instead of {}", model.keys, spec.keys));
+        }
+        let options = model.options.as_ref();
+        let unique = options.and_then(|o| o.unique).unwrap_or(false);
+        if unique != spec.unique {
+            differences.push(format!("unique is {} instead of {}", unique, spec.unique));
+        }
+        let partial_filter = options.and_then(|o| o.partial_filter_expression.as_ref());
+        if partial_filter != spec.partial_filter.as_ref() {
+            differences.push("partial filter differs".to_string());
+        }
+        differences
+    };
+
+    if existing.iter().any(|model| differences(model).is_empty()) {
+        return IndexStatus::Present;
+    }
+
+    let by_name = existing.iter().find(|model| {
+        model
+            .options
+            .as_ref()
+            .and_then(|options| options.name.as_deref())
+            == Some(spec.name)
+    });
+    let by_keys = existing
+        .iter()
+        .find(|model| same_keys(&model.keys, &spec.keys));
+    match by_name.or(by_keys) {
+        Some(model) => IndexStatus::Drifted(differences(model).join(", ")),
+        None => IndexStatus::Missing,
+    }
+}
+
+/// Key documents are equal when they list the same fields in the same order with the same
+/// direction or type; numeric directions compare by value (`1` vs `1.0`).
+fn same_keys(a: &Document, b: &Document) -> bool {
+    fn direction(value: &Bson) -> Option<Bson> {
+        match value {
+            Bson::Int32(v) => Some(Bson::Double(*v as f64)),
+            Bson::Int64(v) => Some(Bson::Double(*v as f64)),
+            Bson::Double(v) => Some(Bson::Double(*v)),
+            Bson::String(_) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    a.len() == b.len()
+        && a.iter()
+            .zip(b.iter())
+            .all(|((ka, va), (kb, vb))| ka == kb && direction(va) == direction(vb))
+}
+
+/// Outcome of the startup index check, by qualified index name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexReport {
+    pub present: Vec<String>,
+    pub created: Vec<String>,
+    /// Missing indexes that were not created because the mode is `Check`.
+    pub missing: Vec<String>,
+    /// Indexes that differ from their declaration, with the differences. Never changed
+    /// automatically, since rebuilding an index can be expensive.
+    pub drifted: Vec<(String, String)>,
+}
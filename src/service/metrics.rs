@@ -0,0 +1,85 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+
+/// Counters of the badge processor, shared with the health and metrics endpoints.
+#[derive(Debug, Default)]
+pub struct ProcessorMetrics {
+    processed: AtomicU64,
+    failed: AtomicU64,
+    panics: AtomicU64,
+    restarts: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ProcessorStats {
+    /// Requests that were processed successfully.
+    pub processed: u64,
+    /// Requests dropped after an error, including panics.
+    pub failed: u64,
+    /// Requests whose processing panicked.
+    pub panics: u64,
+    /// Times the processing task died and was restarted.
+    pub restarts: u64,
+}
+
+impl ProcessorMetrics {
+    pub fn snapshot(&self) -> ProcessorStats {
+        ProcessorStats {
+            processed: self.processed.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            panics: self.panics.load(Ordering::Relaxed),
+            restarts: self.restarts.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn record_processed(&self) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_failed(&self) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_panic(&self) {
+        self.panics.fetch_add(1, Ordering::Relaxed);
+        self.failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_restart(&self) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Builds a response in the Prometheus text exposition format.
+#[derive(Debug, Default)]
+pub struct PrometheusText {
+    body: String,
+}
+
+impl PrometheusText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn counter(&mut self, name: &str, help: &str, value: u64) -> &mut Self {
+        self.metric(name, "counter", help, value)
+    }
+
+    pub fn gauge(&mut self, name: &str, help: &str, value: u64) -> &mut Self {
+        self.metric(name, "gauge", help, value)
+    }
+
+    pub fn finish(self) -> String {
+        self.body
+    }
+
+    fn metric(&mut self, name: &str, kind: &str, help: &str, value: u64) -> &mut Self {
+        // Writing to a String cannot fail
+        let _ = writeln!(self.body, "# HELP {} {}", name, help);
+        let _ = writeln!(self.body, "# TYPE {} {}", name, kind);
+        let _ = writeln!(self.body, "{} {}", name, value);
+        self
+    }
+}
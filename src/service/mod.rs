@@ -1,3 +1,14 @@
+pub mod abuse;
 pub mod badge_processor;
+pub mod change_stream;
+pub mod connectivity;
 pub mod db;
+pub mod health;
+pub mod heartbeat;
+pub mod indexes;
+pub mod metrics;
 pub mod notifier;
+pub mod schema;
+pub mod shutdown;
+pub mod tracking;
+pub mod verification;
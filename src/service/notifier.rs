@@ -2,6 +2,8 @@ use async_trait::async_trait;
 use reqwest::Client;
 use tracing::{error, info};
 
+use crate::config::NotifierConfig;
+
 #[async_trait]
 pub trait Notifier: Send + Sync {
     async fn send_notification(
@@ -10,6 +12,11 @@ pub trait Notifier: Send + Sync {
         recipient: &str,
         metadata: serde_json::Value,
     );
+
+    /// Checks that notifications can be delivered, for the readiness probe.
+    async fn check(&self) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 pub struct HttpNotifier {
@@ -35,15 +42,33 @@ impl HttpNotifier {
         }
     }
 
-    pub fn from_env() -> Self {
-        let notifier_url = std::env::var("NOTIFIER_URL").unwrap_or_default();
-        let notifier_api_key = std::env::var("NOTIFIER_API_KEY").unwrap_or_default();
-        Self::new(notifier_url, notifier_api_key)
+    pub fn from_config(config: &NotifierConfig) -> Self {
+        Self::new(config.url.clone(), config.api_key.clone())
     }
 }
 
 #[async_trait]
 impl Notifier for HttpNotifier {
+    /// Sends `HEAD` to the notifications endpoint, which only accepts `POST`. Any response
+    /// but a server error counts as healthy, e.g. `405 Method Not Allowed`.
+    async fn check(&self) -> Result<(), String> {
+        if self.url.is_empty() {
+            return Ok(());
+        }
+        let response = self
+            .client
+            .head(format!("{}/notifications", self.url))
+            .header("X-API-Key", &self.api_key)
+            .timeout(std::time::Duration::from_secs(2))
+            .send()
+            .await
+            .map_err(|e| format!("Notifier unreachable: {}", e))?;
+        if response.status().is_server_error() {
+            return Err(format!("Notifier unhealthy: {}", response.status()));
+        }
+        Ok(())
+    }
+
     async fn send_notification(
         &self,
         notification_type: &str,
@@ -0,0 +1,288 @@
+use mongodb::bson::{Document, doc};
+
+use crate::config::ConfigSource;
+use crate::error::Error;
+use crate::model::recipe::Recipe;
+use crate::model::user::User;
+
+/// Collection and field names of the host application's MongoDB schema.
+///
+/// User fields badge_forge writes are mapped too, and every read and write goes through the
+/// same names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaMapping {
+    pub user_collection: String,
+    pub recipe_collection: String,
+    pub review_collection: String,
+    pub recipe: RecipeFields,
+    pub user: UserFields,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeFields {
+    pub user_id: String,
+    pub num_likes: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserFields {
+    pub name: String,
+    pub email: String,
+    pub created_at: String,
+    pub level: String,
+    pub badges: String,
+    pub verified: String,
+    pub top_recipe_awards: String,
+    pub verification_locked: String,
+    pub verification_reason: String,
+    pub verification_updated_by: String,
+}
+
+impl Default for SchemaMapping {
+    fn default() -> Self {
+        Self {
+            user_collection: "User".to_string(),
+            recipe_collection: "Recipe".to_string(),
+            review_collection: "BadgeReview".to_string(),
+            recipe: RecipeFields {
+                user_id: "userId".to_string(),
+                num_likes: "numLikes".to_string(),
+                created_at: "createdAt".to_string(),
+            },
+            user: UserFields {
+                name: "name".to_string(),
+                email: "email".to_string(),
+                created_at: "createdAt".to_string(),
+                level: "level".to_string(),
+                badges: "badges".to_string(),
+                verified: "verified".to_string(),
+                top_recipe_awards: "topRecipeAwards".to_string(),
+                verification_locked: "verificationLocked".to_string(),
+                verification_reason: "verificationReason".to_string(),
+                verification_updated_by: "verificationUpdatedBy".to_string(),
+            },
+        }
+    }
+}
+
+/// How many documents of each collection are sampled by the startup schema check.
+pub const SCHEMA_SAMPLE_SIZE: i64 = 20;
+
+impl SchemaMapping {
+    /// Reads the mapping from the `SCHEMA_*` variables, falling back to the Jorbites schema
+    /// for unset ones.
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let defaults = Self::default();
+        let mapping = Self {
+            user_collection: source.string("SCHEMA_USER_COLLECTION", &defaults.user_collection),
+            recipe_collection: source
+                .string("SCHEMA_RECIPE_COLLECTION", &defaults.recipe_collection),
+            review_collection: source
+                .string("SCHEMA_REVIEW_COLLECTION", &defaults.review_collection),
+            recipe: RecipeFields {
+                user_id: source.string("SCHEMA_RECIPE_USER_ID", &defaults.recipe.user_id),
+                num_likes: source.string("SCHEMA_RECIPE_NUM_LIKES", &defaults.recipe.num_likes),
+                created_at: source.string("SCHEMA_RECIPE_CREATED_AT", &defaults.recipe.created_at),
+            },
+            user: UserFields {
+                name: source.string("SCHEMA_USER_NAME", &defaults.user.name),
+                email: source.string("SCHEMA_USER_EMAIL", &defaults.user.email),
+                created_at: source.string("SCHEMA_USER_CREATED_AT", &defaults.user.created_at),
+                level: source.string("SCHEMA_USER_LEVEL", &defaults.user.level),
+                badges: source.string("SCHEMA_USER_BADGES", &defaults.user.badges),
+                verified: source.string("SCHEMA_USER_VERIFIED", &defaults.user.verified),
+                top_recipe_awards: source.string(
+                    "SCHEMA_USER_TOP_RECIPE_AWARDS",
+                    &defaults.user.top_recipe_awards,
+                ),
+                verification_locked: source.string(
+                    "SCHEMA_USER_VERIFICATION_LOCKED",
+                    &defaults.user.verification_locked,
+                ),
+                verification_reason: source.string(
+                    "SCHEMA_USER_VERIFICATION_REASON",
+                    &defaults.user.verification_reason,
+                ),
+                verification_updated_by: source.string(
+                    "SCHEMA_USER_VERIFICATION_UPDATED_BY",
+                    &defaults.user.verification_updated_by,
+                ),
+            },
+        };
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    /// Rejects names MongoDB would interpret as operators or paths, and ambiguous mappings.
+    pub fn validate(&self) -> Result<(), String> {
+        let collections = [
+            &self.user_collection,
+            &self.recipe_collection,
+            &self.review_collection,
+        ];
+        for collection in collections {
+            if collection.is_empty() || collection.contains('$') {
+                return Err(format!("Invalid collection name: {:?}", collection));
+            }
+        }
+        if self.user_collection == self.recipe_collection
+            || self.review_collection == self.user_collection
+            || self.review_collection == self.recipe_collection
+        {
+            return Err("User, recipe and review collections must be different".to_string());
+        }
+
+        let groups = [
+            ("recipe", self.recipe_renames()),
+            ("user", self.user_renames()),
+        ];
+        for (kind, renames) in groups {
+            for (_, field) in &renames {
+                if field.is_empty() || field.starts_with('$') || field.contains('.') {
+                    return Err(format!("Invalid {} field name: {:?}", kind, field));
+                }
+                if *field == "_id" {
+                    return Err(format!("The {} _id field cannot be remapped", kind));
+                }
+            }
+            for (i, (_, field)) in renames.iter().enumerate() {
+                if renames[..i].iter().any(|(_, other)| other == field) {
+                    return Err(format!("{} field {:?} is mapped twice", kind, field));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts a user document of the host schema into a `User`.
+    pub fn user_from_document(&self, document: Document) -> Result<User, Error> {
+        let document = rename_fields(document, &self.user_renames());
+        Ok(mongodb::bson::from_document(document)?)
+    }
+
+    /// Converts a recipe document of the host schema into a `Recipe`.
+    pub fn recipe_from_document(&self, document: Document) -> Result<Recipe, Error> {
+        let document = rename_fields(document, &self.recipe_renames());
+        Ok(mongodb::bson::from_document(document)?)
+    }
+
+    /// Projection of the recipe fields badge_forge reads.
+    pub fn recipe_projection(&self) -> Document {
+        doc! {
+            "_id": 1,
+            &self.recipe.user_id: 1,
+            &self.recipe.num_likes: 1,
+            &self.recipe.created_at: 1
+        }
+    }
+
+    /// Reports how many of the sampled documents contain each mapped field.
+    pub fn check_samples(&self, recipes: &[Document], users: &[Document]) -> Vec<FieldCoverage> {
+        let recipe_fields = [
+            (&self.recipe.user_id, true),
+            (&self.recipe.num_likes, false),
+            (&self.recipe.created_at, true),
+        ];
+        let user_fields = [
+            (&self.user.name, false),
+            (&self.user.email, false),
+            (&self.user.created_at, false),
+        ];
+
+        let coverage = |collection: &str, fields: &[(&String, bool)], samples: &[Document]| {
+            fields
+                .iter()
+                .map(|(field, required)| FieldCoverage {
+                    collection: collection.to_string(),
+                    field: field.to_string(),
+                    required: *required,
+                    present: samples.iter().filter(|doc| doc.contains_key(field)).count(),
+                    sampled: samples.len(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut report = coverage(&self.recipe_collection, &recipe_fields, recipes);
+        report.extend(coverage(&self.user_collection, &user_fields, users));
+        report
+    }
+
+    /// `(canonical, mapped)` names of the recipe fields.
+    fn recipe_renames(&self) -> Vec<(&'static str, &str)> {
+        vec![
+            ("userId", self.recipe.user_id.as_str()),
+            ("numLikes", self.recipe.num_likes.as_str()),
+            ("createdAt", self.recipe.created_at.as_str()),
+        ]
+    }
+
+    /// `(canonical, mapped)` names of the user fields.
+    fn user_renames(&self) -> Vec<(&'static str, &str)> {
+        vec![
+            ("name", self.user.name.as_str()),
+            ("email", self.user.email.as_str()),
+            ("createdAt", self.user.created_at.as_str()),
+            ("level", self.user.level.as_str()),
+            ("badges", self.user.badges.as_str()),
+            ("verified", self.user.verified.as_str()),
+            ("topRecipeAwards", self.user.top_recipe_awards.as_str()),
+            ("verificationLocked", self.user.verification_locked.as_str()),
+            ("verificationReason", self.user.verification_reason.as_str()),
+            (
+                "verificationUpdatedBy",
+                self.user.verification_updated_by.as_str(),
+            ),
+        ]
+    }
+}
+
+/// How often a mapped field occurs in the sampled documents of its collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldCoverage {
+    pub collection: String,
+    pub field: String,
+    /// Required fields missing from every sampled document fail the startup check.
+    pub required: bool,
+    pub present: usize,
+    pub sampled: usize,
+}
+
+impl FieldCoverage {
+    /// The field is absent from every sampled document, which usually means a wrong mapping.
+    pub fn is_missing(&self) -> bool {
+        self.sampled > 0 && self.present == 0
+    }
+
+    /// The field is absent from some, but not all, sampled documents.
+    pub fn is_partial(&self) -> bool {
+        self.present > 0 && self.present < self.sampled
+    }
+}
+
+/// Moves the values of the mapped fields to their canonical names. Values are collected
+/// before any key is removed, so mappings that swap names are handled correctly.
+fn rename_fields(mut document: Document, renames: &[(&str, &str)]) -> Document {
+    if renames
+        .iter()
+        .all(|(canonical, mapped)| canonical == mapped)
+    {
+        return document;
+    }
+
+    let values: Vec<_> = renames
+        .iter()
+        .map(|(canonical, mapped)| (*canonical, document.get(*mapped).cloned()))
+        .collect();
+    for (canonical, mapped) in renames {
+        document.remove(*canonical);
+        document.remove(*mapped);
+    }
+    for (canonical, value) in values {
+        if let Some(value) = value {
+            document.insert(canonical, value);
+        }
+    }
+    document
+}
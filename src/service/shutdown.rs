@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::config::ConfigSource;
+use crate::error::Error;
+use crate::model::level::LevelRequest;
+use crate::queue::BadgeUpdateQueue;
+
+/// How often the drain checks whether the queue is empty.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Whether the service is shutting down. Once triggered it stays triggered.
+///
+/// Clones observe the same state.
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    state: Arc<watch::Sender<bool>>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trigger(&self) {
+        if !self.state.send_replace(true) {
+            info!("Shutting down, no longer accepting badge updates");
+        }
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.state.borrow()
+    }
+
+    pub async fn wait(&self) {
+        let mut receiver = self.state.subscribe();
+        // The sender is owned by `self`, so the channel cannot close while waiting
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// Shutdown settings, read from `SHUTDOWN_TIMEOUT_SECS` and `PENDING_REQUESTS_PATH`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShutdownConfig {
+    /// How long the processor may keep draining the queue after a shutdown signal.
+    pub timeout: Duration,
+    /// Where requests still pending at the deadline are saved, and restored from at startup.
+    pub pending_path: Option<PathBuf>,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(25),
+            pending_path: None,
+        }
+    }
+}
+
+impl ShutdownConfig {
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let defaults = Self::default();
+        Ok(Self {
+            timeout: Duration::from_secs(
+                source.u32("SHUTDOWN_TIMEOUT_SECS", defaults.timeout.as_secs() as u32)? as u64,
+            ),
+            pending_path: source
+                .optional_string("PENDING_REQUESTS_PATH")
+                .map(PathBuf::from),
+        })
+    }
+}
+
+/// Completes on SIGINT or, on Unix, SIGTERM.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received SIGINT"),
+        _ = terminate => info!("Received SIGTERM"),
+    }
+}
+
+/// Waits until the queue has no pending requests or `timeout` elapses, and returns the
+/// requests still pending then. The request being processed at the deadline is included.
+pub async fn drain_queue(queue: &dyn BadgeUpdateQueue, timeout: Duration) -> Vec<LevelRequest> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let pending = queue.get_pending_requests().await;
+        if pending.is_empty() || Instant::now() >= deadline {
+            return pending;
+        }
+        tokio::time::sleep(DRAIN_POLL_INTERVAL.min(deadline - Instant::now())).await;
+    }
+}
+
+/// Writes pending requests to `path` as JSON, replacing the file.
+pub fn save_pending_requests(path: &Path, requests: &[LevelRequest]) -> Result<(), Error> {
+    let json = serde_json::to_vec_pretty(requests)
+        .map_err(|e| Error::Permanent(format!("Failed to serialize pending requests: {}", e)))?;
+    // Write to a temporary file first so a crash cannot leave a truncated file behind
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|e| {
+            Error::Permanent(format!(
+                "Failed to write pending requests to {}: {}",
+                path.display(),
+                e
+            ))
+        })
+}
+
+/// Reads the requests saved by `save_pending_requests` and removes the file, so they are
+/// restored only once. A missing file yields no requests.
+pub fn take_pending_requests(path: &Path) -> Result<Vec<LevelRequest>, Error> {
+    let json = match std::fs::read(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(Error::Permanent(format!(
+                "Failed to read pending requests from {}: {}",
+                path.display(),
+                e
+            )));
+        }
+    };
+    let requests = serde_json::from_slice(&json).map_err(|e| {
+        Error::Permanent(format!(
+            "Invalid pending requests file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    std::fs::remove_file(path).map_err(|e| {
+        Error::Permanent(format!(
+            "Failed to remove pending requests file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(requests)
+}
@@ -0,0 +1,240 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::ConfigSource;
+use crate::model::level::LevelRequest;
+
+/// Transitions kept per request; the oldest after the first are dropped beyond this, e.g.
+/// when a request keeps waiting for the database.
+const MAX_TRANSITIONS: usize = 32;
+
+/// Settings of the request tracker, read from `REQUEST_TRACKING_TTL_SECS` and
+/// `REQUEST_TRACKING_MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackingConfig {
+    /// How long a request is kept after its last change.
+    pub ttl: Duration,
+    /// Requests kept at most; the oldest are dropped first.
+    pub max_requests: usize,
+}
+
+impl Default for TrackingConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(3600),
+            max_requests: 10_000,
+        }
+    }
+}
+
+impl TrackingConfig {
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let defaults = Self::default();
+        Ok(Self {
+            ttl: Duration::from_secs(
+                source.u32("REQUEST_TRACKING_TTL_SECS", defaults.ttl.as_secs() as u32)? as u64,
+            ),
+            max_requests: source.u32("REQUEST_TRACKING_MAX", defaults.max_requests as u32)?
+                as usize,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestState {
+    Queued,
+    Processing,
+    /// Failed with a retryable error and will be processed again.
+    Retrying,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StateTransition {
+    pub state: RequestState,
+    pub at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// What a completed request changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RequestOutcome {
+    pub previous_level: i32,
+    pub level: i32,
+    pub badges_added: Vec<String>,
+    pub verified: bool,
+    pub newly_verified: bool,
+    /// New badges or verification were held for an admin review instead of being granted.
+    pub held_for_review: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrackedRequest {
+    pub request_id: String,
+    pub user_id: String,
+    pub state: RequestState,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub transitions: Vec<StateTransition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<RequestOutcome>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Requests {
+    by_id: HashMap<String, TrackedRequest>,
+    /// Request ids in the order they were first seen, to drop the oldest.
+    order: VecDeque<String>,
+}
+
+/// Recent badge update requests and what became of them, kept in memory. Clones share the
+/// same requests. The default tracks nothing.
+#[derive(Debug, Clone)]
+pub struct RequestTracker {
+    config: TrackingConfig,
+    requests: Arc<Mutex<Requests>>,
+}
+
+impl Default for RequestTracker {
+    fn default() -> Self {
+        Self::new(TrackingConfig {
+            ttl: Duration::ZERO,
+            max_requests: 0,
+        })
+    }
+}
+
+impl RequestTracker {
+    pub fn new(config: TrackingConfig) -> Self {
+        Self {
+            config,
+            requests: Arc::default(),
+        }
+    }
+
+    pub fn queued(&self, request: &LevelRequest) {
+        self.record(request, RequestState::Queued, None, |_| {});
+    }
+
+    pub fn processing(&self, request: &LevelRequest) {
+        self.record(request, RequestState::Processing, None, |_| {});
+    }
+
+    pub fn retrying(&self, request: &LevelRequest, reason: String) {
+        self.record(request, RequestState::Retrying, Some(reason), |_| {});
+    }
+
+    pub fn completed(&self, request: &LevelRequest, outcome: RequestOutcome) {
+        self.record(request, RequestState::Completed, None, |tracked| {
+            tracked.outcome = Some(outcome);
+            tracked.error = None;
+        });
+    }
+
+    pub fn failed(&self, request: &LevelRequest, error: String) {
+        self.record(request, RequestState::Failed, None, |tracked| {
+            tracked.error = Some(error);
+        });
+    }
+
+    /// The request with `request_id`, unless it expired or was never seen.
+    pub fn get(&self, request_id: &str) -> Option<TrackedRequest> {
+        let requests = self.lock();
+        requests
+            .by_id
+            .get(request_id)
+            .filter(|tracked| !self.is_expired(tracked, Utc::now()))
+            .cloned()
+    }
+
+    fn record(
+        &self,
+        request: &LevelRequest,
+        state: RequestState,
+        message: Option<String>,
+        update: impl FnOnce(&mut TrackedRequest),
+    ) {
+        if self.config.max_requests == 0 || self.config.ttl.is_zero() {
+            return;
+        }
+        let now = Utc::now();
+        let mut requests = self.lock();
+        if requests.by_id.contains_key(&request.request_id) {
+            // A worker may take a request before the queue records it as queued
+            if state == RequestState::Queued {
+                return;
+            }
+        } else {
+            self.make_room(&mut requests, now);
+            requests.order.push_back(request.request_id.clone());
+        }
+        let tracked = requests
+            .by_id
+            .entry(request.request_id.clone())
+            .or_insert_with(|| TrackedRequest {
+                request_id: request.request_id.clone(),
+                user_id: request.user_id.clone(),
+                state,
+                created_at: request.created_at,
+                updated_at: now,
+                transitions: vec![StateTransition {
+                    state: RequestState::Queued,
+                    at: request.created_at,
+                    message: None,
+                }],
+                outcome: None,
+                error: None,
+            });
+        if state == RequestState::Queued {
+            return;
+        }
+        if tracked.transitions.len() >= MAX_TRANSITIONS {
+            tracked.transitions.remove(1);
+        }
+        tracked.transitions.push(StateTransition {
+            state,
+            at: now,
+            message,
+        });
+        tracked.state = state;
+        tracked.updated_at = now;
+        update(tracked);
+    }
+
+    /// Drops expired requests from the front, and the oldest ones while the tracker is full.
+    fn make_room(&self, requests: &mut Requests, now: DateTime<Utc>) {
+        while let Some(oldest) = requests.order.front() {
+            let expired = requests
+                .by_id
+                .get(oldest)
+                .is_none_or(|tracked| self.is_expired(tracked, now));
+            if !expired && requests.by_id.len() < self.config.max_requests {
+                break;
+            }
+            if let Some(oldest) = requests.order.pop_front() {
+                requests.by_id.remove(&oldest);
+            }
+        }
+    }
+
+    fn is_expired(&self, tracked: &TrackedRequest, now: DateTime<Utc>) -> bool {
+        let ttl = chrono::Duration::from_std(self.config.ttl).unwrap_or(chrono::Duration::MAX);
+        now - tracked.updated_at > ttl
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Requests> {
+        // A poisoned map only holds plain data, which stays valid
+        self.requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
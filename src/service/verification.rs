@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::ConfigSource;
+use crate::model::stats::UserStats;
+
+/// Criteria a user must meet to be verified automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct VerificationPolicy {
+    pub min_recipes: u32,
+    pub min_total_likes: u32,
+    pub min_account_age_days: u32,
+    pub min_active_weeks: u32,
+}
+
+impl Default for VerificationPolicy {
+    fn default() -> Self {
+        Self {
+            min_recipes: 30,
+            min_total_likes: 0,
+            min_account_age_days: 0,
+            min_active_weeks: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Criterion {
+    Recipes,
+    TotalLikes,
+    AccountAgeDays,
+    ActiveWeeks,
+}
+
+/// A criterion the user does not meet yet, with the required and current values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MissingCriterion {
+    pub criterion: Criterion,
+    pub required: u32,
+    pub actual: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerificationReport {
+    pub eligible: bool,
+    pub missing: Vec<MissingCriterion>,
+}
+
+impl VerificationPolicy {
+    /// Reads the policy from `VERIFICATION_MIN_RECIPES`, `VERIFICATION_MIN_TOTAL_LIKES`,
+    /// `VERIFICATION_MIN_ACCOUNT_AGE_DAYS` and `VERIFICATION_MIN_ACTIVE_WEEKS`, falling back
+    /// to the defaults for unset settings.
+    pub fn from_source(source: &ConfigSource) -> Result<Self, String> {
+        let defaults = Self::default();
+        Ok(Self {
+            min_recipes: source.u32("VERIFICATION_MIN_RECIPES", defaults.min_recipes)?,
+            min_total_likes: source
+                .u32("VERIFICATION_MIN_TOTAL_LIKES", defaults.min_total_likes)?,
+            min_account_age_days: source.u32(
+                "VERIFICATION_MIN_ACCOUNT_AGE_DAYS",
+                defaults.min_account_age_days,
+            )?,
+            min_active_weeks: source
+                .u32("VERIFICATION_MIN_ACTIVE_WEEKS", defaults.min_active_weeks)?,
+        })
+    }
+
+    /// Checks every criterion against the user's activity. Accounts without a known
+    /// creation date have an age of 0 days.
+    pub fn evaluate(
+        &self,
+        stats: &UserStats,
+        account_created_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> VerificationReport {
+        let account_age_days = account_created_at
+            .map(|created_at| (now - created_at).num_days().max(0) as u32)
+            .unwrap_or(0);
+
+        let checks = [
+            (Criterion::Recipes, self.min_recipes, stats.num_recipes),
+            (
+                Criterion::TotalLikes,
+                self.min_total_likes,
+                stats.total_likes,
+            ),
+            (
+                Criterion::AccountAgeDays,
+                self.min_account_age_days,
+                account_age_days,
+            ),
+            (
+                Criterion::ActiveWeeks,
+                self.min_active_weeks,
+                stats.active_weeks,
+            ),
+        ];
+
+        let missing: Vec<MissingCriterion> = checks
+            .into_iter()
+            .filter(|(_, required, actual)| actual < required)
+            .map(|(criterion, required, actual)| MissingCriterion {
+                criterion,
+                required,
+                actual,
+            })
+            .collect();
+
+        VerificationReport {
+            eligible: missing.is_empty(),
+            missing,
+        }
+    }
+}
@@ -2,16 +2,16 @@ use crate::model::recipe::Recipe;
 use chrono::{Datelike, NaiveDate};
 use std::collections::HashSet;
 
+/// Badges granted once a user reaches the given level.
+pub const LEVEL_BADGES: [(i32, &str); 3] =
+    [(100, "level_100"), (250, "level_250"), (500, "level_500")];
+
 pub fn assign_badges(user_badges: &mut Vec<String>, user_level: i32, recipes: Vec<Recipe>) {
     // check for level-based badges
-    if user_level >= 100 && !user_badges.contains(&"level_100".to_string()) {
-        user_badges.push("level_100".to_string());
-    }
-    if user_level >= 250 && !user_badges.contains(&"level_250".to_string()) {
-        user_badges.push("level_250".to_string());
-    }
-    if user_level >= 500 && !user_badges.contains(&"level_500".to_string()) {
-        user_badges.push("level_500".to_string());
+    for (level, badge) in LEVEL_BADGES {
+        if user_level >= level && !user_badges.iter().any(|b| b == badge) {
+            user_badges.push(badge.to_string());
+        }
     }
     // check for streak badges
     if !user_badges.contains(&"month_streak".to_string()) && is_month_streak(&recipes) {
@@ -22,6 +22,36 @@ pub fn assign_badges(user_badges: &mut Vec<String>, user_level: i32, recipes: Ve
     }
 }
 
+/// Badges that can only be evaluated from the user's full recipe history.
+pub const STREAK_BADGES: [&str; 2] = ["week_streak", "month_streak"];
+
+/// Whether `assign_badges` needs the user's recipes, i.e. some streak badge is still missing.
+pub fn needs_recipe_history(user_badges: &[String]) -> bool {
+    STREAK_BADGES
+        .iter()
+        .any(|badge| !user_badges.iter().any(|b| b == badge))
+}
+
+/// Win counts at which a repeated top recipe winner earns a tiered badge.
+pub const TOP_RECIPE_WIN_TIERS: [u32; 2] = [5, 10];
+
+/// Whether `badge` is named like a tiered top recipe badge, e.g. `recipe_of_the_week_5x`.
+pub fn is_tier_badge(badge: &str) -> bool {
+    TOP_RECIPE_WIN_TIERS
+        .iter()
+        .any(|tier| badge.ends_with(&format!("_{}x", tier)))
+}
+
+/// Tiered badges derived from the number of wins of a top recipe badge,
+/// e.g. `recipe_of_the_week_5x` after five weekly wins.
+pub fn top_recipe_tier_badges(badge: &str, win_count: u32) -> Vec<String> {
+    TOP_RECIPE_WIN_TIERS
+        .iter()
+        .filter(|tier| win_count >= **tier)
+        .map(|tier| format!("{}_{}x", badge, tier))
+        .collect()
+}
+
 // At least one recipe per day for 7 consecutive days
 pub fn is_week_streak(recipes: &[Recipe]) -> bool {
     if recipes.len() < 7 {
@@ -1,3 +1,4 @@
+use crate::config::AxiomConfig;
 use tracing::Subscriber;
 use tracing::field::Visit;
 use tracing_subscriber::Layer;
@@ -24,6 +25,9 @@ impl Visit for JsonVisitor {
     }
 }
 
+/// Fields of a span, added to the events logged inside it.
+struct SpanFields(serde_json::Map<String, serde_json::Value>);
+
 struct AxiomLoggingLayer {
     sender: tokio::sync::mpsc::UnboundedSender<serde_json::Value>,
     service: &'static str,
@@ -33,10 +37,35 @@ impl<S> Layer<S> for AxiomLoggingLayer
 where
     S: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
 {
-    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: Context<'_, S>,
+    ) {
         let mut visitor = JsonVisitor {
             fields: serde_json::Map::new(),
         };
+        attrs.record(&mut visitor);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(visitor.fields));
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = JsonVisitor {
+            fields: serde_json::Map::new(),
+        };
+        // Outermost span first, so inner spans and the event override its fields
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    visitor
+                        .fields
+                        .extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+                }
+            }
+        }
         event.record(&mut visitor);
 
         let metadata = event.metadata();
@@ -93,15 +122,37 @@ async fn flush_logs(
     }
 }
 
-pub fn init_logging(service_name: &'static str) {
+/// Flushes the logs buffered for Axiom on demand, e.g. right before exiting.
+#[derive(Clone, Default)]
+pub struct LogFlusher {
+    requests: Option<tokio::sync::mpsc::UnboundedSender<tokio::sync::oneshot::Sender<()>>>,
+}
+
+impl LogFlusher {
+    /// Sends everything logged so far and waits until it has been sent. Does nothing when
+    /// Axiom is not configured.
+    pub async fn flush(&self) {
+        let Some(requests) = &self.requests else {
+            return;
+        };
+        let (done, flushed) = tokio::sync::oneshot::channel();
+        if requests.send(done).is_ok() {
+            let _ = flushed.await;
+        }
+    }
+}
+
+/// Logs to stdout, and to Axiom when configured.
+pub fn init_logging(service_name: &'static str, axiom: Option<&AxiomConfig>) -> LogFlusher {
     let fmt_layer =
         tracing_subscriber::fmt::layer().with_filter(tracing_subscriber::filter::LevelFilter::INFO);
 
-    let axiom_token = std::env::var("AXIOM_TOKEN").ok();
-    let axiom_dataset = std::env::var("AXIOM_DATASET").ok();
-
-    let axiom_layer = if let (Some(token), Some(dataset)) = (axiom_token, axiom_dataset) {
+    let mut flusher = LogFlusher::default();
+    let axiom_layer = if let Some(AxiomConfig { token, dataset }) = axiom.cloned() {
         let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<serde_json::Value>();
+        let (flush_tx, mut flush_rx) =
+            tokio::sync::mpsc::unbounded_channel::<tokio::sync::oneshot::Sender<()>>();
+        flusher.requests = Some(flush_tx);
 
         // Spawn background worker to batch-send logs
         tokio::spawn(async move {
@@ -118,6 +169,16 @@ pub fn init_logging(service_name: &'static str) {
                             buffer.clear();
                         }
                     }
+                    Some(done) = flush_rx.recv() => {
+                        while let Ok(event) = rx.try_recv() {
+                            buffer.push(event);
+                        }
+                        if !buffer.is_empty() {
+                            flush_logs(&buffer, &client, &dataset, &token).await;
+                            buffer.clear();
+                        }
+                        let _ = done.send(());
+                    }
                     _ = interval.tick() => {
                         if !buffer.is_empty() {
                             flush_logs(&buffer, &client, &dataset, &token).await;
@@ -140,6 +201,7 @@ pub fn init_logging(service_name: &'static str) {
     };
 
     Registry::default().with(axiom_layer).with(fmt_layer).init();
+    flusher
 }
 
 #[cfg(test)]
@@ -170,4 +232,27 @@ mod tests {
         assert_eq!(event["some_key"], "some_val");
         assert!(event["_time"].is_string());
     }
+
+    #[tokio::test]
+    async fn test_logging_layer_includes_span_fields() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<serde_json::Value>();
+
+        let layer = AxiomLoggingLayer {
+            sender: tx,
+            service: "test_service",
+        };
+
+        let subscriber = Registry::default().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request", api_key = "jorbites");
+            let _guard = span.enter();
+            tracing::info!("Inside a span");
+        });
+
+        let event = rx.try_recv().expect("Should have received an event");
+
+        assert_eq!(event["message"], "Inside a span");
+        assert_eq!(event["api_key"], "jorbites");
+    }
 }
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{MockDatabase, MockNotifier, build_test_state};
+    use axum::http::StatusCode;
+    use axum::test_helpers::TestClient;
+    use badge_forge::api::{route::create_router, state::AppState};
+    use badge_forge::middleware::auth::{ApiKey, ApiKeys, Scope};
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::queue::InMemoryQueue;
+    use chrono::{Duration, Utc};
+    use mongodb::bson::oid::ObjectId;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    fn key(name: &str, secret: &str, scopes: &[Scope]) -> ApiKey {
+        ApiKey {
+            name: name.to_string(),
+            key: secret.to_string(),
+            scopes: scopes.to_vec(),
+            expires_at: None,
+        }
+    }
+
+    /// The receiver must be kept alive for `/update` to succeed.
+    fn client(keys: Vec<ApiKey>) -> (TestClient, mpsc::Receiver<LevelRequest>) {
+        let (queue, receiver) = InMemoryQueue::new(10);
+        let state = AppState {
+            api_keys: ApiKeys::new(keys).unwrap(),
+            badge_queue: Arc::new(queue),
+            ..build_test_state(Arc::new(MockDatabase::new()), Arc::new(MockNotifier::new()))
+        };
+        (TestClient::new(create_router(Arc::new(state))), receiver)
+    }
+
+    #[tokio::test]
+    async fn test_scopes_restrict_routes() {
+        let (client, _receiver) = client(vec![
+            key("jorbites", "enqueue-secret", &[Scope::Enqueue]),
+            key("dashboard", "read-secret", &[Scope::Read]),
+        ]);
+        let update = json!({ "user_id": ObjectId::new().to_hex() });
+
+        let response = client
+            .post("/update")
+            .header("X-API-Key", "enqueue-secret")
+            .json(&update)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = client
+            .get("/status")
+            .header("X-API-Key", "enqueue-secret")
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(response.text().await, "API key lacks the read scope");
+
+        let response = client
+            .get("/status")
+            .header("X-API-Key", "read-secret")
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = client
+            .post("/update")
+            .header("X-API-Key", "read-secret")
+            .json(&update)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = client
+            .get("/admin/reviews")
+            .header("X-API-Key", "read-secret")
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = client.get("/status").header("X-API-Key", "wrong").await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_rotation_accepts_old_key_until_it_expires() {
+        let mut old = key("voting", "old-secret", &[Scope::Read]);
+        old.expires_at = Some(Utc::now() + Duration::hours(1));
+        let mut retired = key("voting", "retired-secret", &[Scope::Read]);
+        retired.expires_at = Some(Utc::now() - Duration::hours(1));
+        let (client, _receiver) = client(vec![
+            key("voting", "new-secret", &[Scope::Read]),
+            old,
+            retired,
+        ]);
+
+        for secret in ["new-secret", "old-secret"] {
+            let response = client.get("/status").header("X-API-Key", secret).await;
+            assert_eq!(response.status(), StatusCode::OK, "{}", secret);
+        }
+        let response = client
+            .get("/status")
+            .header("X-API-Key", "retired-secret")
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
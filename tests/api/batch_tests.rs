@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{MockDatabase, MockNotifier, build_test_state, test_api_key};
+    use axum::http::StatusCode;
+    use axum::test_helpers::{TestClient, TestResponse};
+    use badge_forge::api::{route::create_router, state::AppState};
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::queue::{BadgeUpdateQueue, InMemoryQueue};
+    use mongodb::bson::oid::ObjectId;
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    fn client(queue_size: usize) -> (TestClient, Arc<InMemoryQueue>, mpsc::Receiver<LevelRequest>) {
+        let (queue, receiver) = InMemoryQueue::new(queue_size);
+        let queue = Arc::new(queue);
+        let state = AppState {
+            badge_queue: queue.clone(),
+            batch_max_size: 5,
+            ..build_test_state(Arc::new(MockDatabase::new()), Arc::new(MockNotifier::new()))
+        };
+        (
+            TestClient::new(create_router(Arc::new(state))),
+            queue,
+            receiver,
+        )
+    }
+
+    async fn batch(client: &TestClient, user_ids: &[String]) -> TestResponse {
+        client
+            .post("/update/batch")
+            .header("X-API-Key", test_api_key())
+            .json(&json!({ "user_ids": user_ids }))
+            .await
+    }
+
+    fn user_ids(count: usize) -> Vec<String> {
+        (0..count).map(|_| ObjectId::new().to_hex()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_batch_update() {
+        let (client, queue, _receiver) = client(10);
+        let mut ids = user_ids(3);
+        ids.push(ids[0].clone());
+
+        let response = batch(&client, &ids).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: Value = response.json().await;
+        assert_eq!(body["queued"], 3);
+        assert_eq!(body["rejected"], 0);
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 4);
+        for (result, user_id) in results.iter().zip(&ids) {
+            assert_eq!(result["user_id"], user_id.as_str());
+        }
+        assert_eq!(results[3]["status"], "duplicate");
+
+        let pending = queue.get_pending_requests().await;
+        assert_eq!(pending.len(), 3);
+        for (result, request) in results.iter().zip(&pending) {
+            assert_eq!(result["status"], "queued");
+            assert_eq!(result["request_id"], request.request_id.as_str());
+            assert_eq!(result["user_id"], request.user_id.as_str());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_validation() {
+        let (client, queue, _receiver) = client(10);
+
+        let mut ids = user_ids(2);
+        ids.insert(1, "not-an-id".to_string());
+        let response = batch(&client, &ids).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: Value = response.json().await;
+        assert_eq!(body["message"], "Invalid user ID format: not-an-id");
+
+        let response = batch(&client, &user_ids(6)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: Value = response.json().await;
+        assert_eq!(body["message"], "A batch holds at most 5 user ids, got 6");
+
+        let response = batch(&client, &[]).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Nothing of a rejected batch is queued
+        assert!(queue.get_pending_requests().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batch_reports_items_rejected_by_full_queue() {
+        let (client, queue, _receiver) = client(2);
+
+        let response = batch(&client, &user_ids(4)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["Retry-After"], "5");
+        let body: Value = response.json().await;
+        assert_eq!(body["queued"], 2);
+        assert_eq!(body["rejected"], 2);
+        let statuses: Vec<&str> = body["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| result["status"].as_str().unwrap())
+            .collect();
+        assert_eq!(statuses, ["queued", "queued", "rejected", "rejected"]);
+        assert!(body["results"][2].get("request_id").is_none());
+        assert_eq!(queue.get_pending_requests().await.len(), 2);
+    }
+}
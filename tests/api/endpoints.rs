@@ -1,10 +1,22 @@
 #[cfg(test)]
 mod endpoints_tests {
-    use crate::utils::test_utils::{setup_test_client, setup_test_client_with_db};
+    use crate::utils::test_utils::{
+        MockDatabase, MockNotifier, TestBackend, build_test_state, create_test_recipes,
+        create_test_user, setup_test_client, setup_test_client_with_backend,
+        setup_test_client_with_db,
+    };
     use axum::http::StatusCode;
+    use axum::test_helpers::TestClient;
+    use badge_forge::api::{route::create_router, state::AppState};
+    use badge_forge::error::Error;
+    use badge_forge::model::category::{CategoryRegistry, CustomCategory, Period};
+    use badge_forge::model::review::{AbuseSignal, BadgeReview, ReviewStatus};
     use badge_forge::model::user::User;
+    use badge_forge::service::connectivity::Connectivity;
+    use badge_forge::service::heartbeat::Heartbeat;
     use mongodb::bson::oid::ObjectId;
     use serde_json::json;
+    use std::sync::Arc;
 
     fn get_test_api_key() -> String {
         dotenv::dotenv().ok();
@@ -21,6 +33,37 @@ mod endpoints_tests {
         assert!(body.contains("ok"));
     }
 
+    #[tokio::test]
+    async fn test_liveness_and_readiness() {
+        let db = Arc::new(MockDatabase::default());
+        let heartbeat = Heartbeat::new();
+        let connectivity = Connectivity::new(false);
+        let state = AppState {
+            connectivity: connectivity.clone(),
+            processor_heartbeat: heartbeat.clone(),
+            ..build_test_state(db, Arc::new(MockNotifier::new()))
+        };
+        let client = TestClient::new(create_router(Arc::new(state)));
+
+        let response = client.get("/health/live").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client.get("/health/ready").await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = response.json().await;
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["components"]["database"]["status"], "fail");
+        assert_eq!(body["components"]["processor"]["status"], "fail");
+        assert_eq!(body["components"]["queue"]["status"], "ok");
+
+        connectivity.set_ready(true);
+        heartbeat.beat();
+        let response = client.get("/health/ready").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await;
+        assert_eq!(body["status"], "ok");
+    }
+
     #[tokio::test]
     async fn test_version_check() {
         let client = setup_test_client().await;
@@ -110,11 +153,9 @@ mod endpoints_tests {
         assert!(body["message"].as_str().unwrap().contains("User not found"));
     }
 
-    #[tokio::test]
-    async fn test_award_top_recipe_success() {
-        let (client, db, notifier) = setup_test_client_with_db().await;
+    async fn test_award_top_recipe_success<D: TestBackend>() {
+        let (client, db, notifier) = setup_test_client_with_backend::<D>().await;
 
-        // Insert a dummy user directly into MockDatabase
         let user_oid = ObjectId::new();
         let email = format!("test_winner_{}@example.com", user_oid.to_hex());
         let dummy_user = User {
@@ -124,12 +165,14 @@ mod endpoints_tests {
             level: 1,
             badges: vec![],
             verified: Some(false),
+            top_recipe_awards: vec![],
+            created_at: None,
+            verification_locked: None,
+            verification_reason: None,
+            verification_updated_by: None,
         };
 
-        {
-            let mut users = db.users.lock().unwrap();
-            users.insert(user_oid, dummy_user);
-        }
+        db.seed_user(dummy_user, vec![]);
 
         // Award weekly badge
         let response = client
@@ -147,15 +190,12 @@ mod endpoints_tests {
         assert_eq!(body["badge"], "recipe_of_the_week");
 
         // Verify badge added in DB
-        {
-            let users = db.users.lock().unwrap();
-            let user_in_db = users.get(&user_oid).unwrap();
-            assert!(
-                user_in_db
-                    .badges
-                    .contains(&"recipe_of_the_week".to_string())
-            );
-        }
+        let user_in_db = db.stored_user(&user_oid).await;
+        assert!(
+            user_in_db
+                .badges
+                .contains(&"recipe_of_the_week".to_string())
+        );
 
         // Verify notification was sent
         {
@@ -185,4 +225,489 @@ mod endpoints_tests {
             assert_eq!(notes.len(), 1);
         }
     }
+
+    fn insert_test_user<D: TestBackend>(db: &Arc<D>, badges: Vec<String>) -> (ObjectId, String) {
+        let user = create_test_user(badges);
+        let (user_oid, email) = (user._id, user.email.clone().unwrap());
+        db.seed_user(user, vec![]);
+        (user_oid, email)
+    }
+
+    async fn test_award_top_recipe_counts_multiple_wins<D: TestBackend>() {
+        let (client, db, notifier) = setup_test_client_with_backend::<D>().await;
+        let (user_oid, _) = insert_test_user(&db, vec![]);
+
+        for week in 1..=5 {
+            let response = client
+                .post("/award-top-recipe")
+                .header("X-API-Key", get_test_api_key())
+                .json(&json!({
+                    "category": "week",
+                    "user_id": user_oid.to_hex(),
+                    "recipe_id": ObjectId::new().to_hex(),
+                    "period": format!("2025-W{:02}", week)
+                }))
+                .await;
+
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+            assert_eq!(body["status"], "success");
+            assert_eq!(body["win_count"], week);
+            assert_eq!(body["period"], format!("2025-W{:02}", week));
+            if week == 5 {
+                assert_eq!(body["tier_badges"], json!(["recipe_of_the_week_5x"]));
+            } else {
+                assert_eq!(body["tier_badges"], json!([]));
+            }
+        }
+
+        let user_in_db = db.stored_user(&user_oid).await;
+        assert_eq!(user_in_db.top_recipe_awards.len(), 5);
+        assert!(
+            user_in_db
+                .top_recipe_awards
+                .iter()
+                .all(|a| a.recipe_id.is_some())
+        );
+        assert_eq!(
+            user_in_db
+                .badges
+                .iter()
+                .filter(|b| *b == "recipe_of_the_week")
+                .count(),
+            1
+        );
+        assert!(
+            user_in_db
+                .badges
+                .contains(&"recipe_of_the_week_5x".to_string())
+        );
+
+        // One notification per win plus one for the tier badge
+        {
+            let notes = notifier.notifications.lock().unwrap();
+            assert_eq!(notes.len(), 6);
+            assert_eq!(notes[4].2["winCount"], 5);
+            assert_eq!(notes[5].2["badgeName"], "recipe_of_the_week_5x");
+        }
+
+        // Same period again is a duplicate and keeps the count
+        let response_dup = client
+            .post("/award-top-recipe")
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({
+                "category": "week",
+                "user_id": user_oid.to_hex(),
+                "period": "2025-W05"
+            }))
+            .await;
+        let body_dup: serde_json::Value = serde_json::from_str(&response_dup.text().await).unwrap();
+        assert_eq!(body_dup["status"], "already_awarded");
+        assert_eq!(body_dup["win_count"], 5);
+    }
+
+    async fn test_award_top_recipe_counts_legacy_badge<D: TestBackend>() {
+        let (client, db, _) = setup_test_client_with_backend::<D>().await;
+        let (user_oid, _) = insert_test_user(&db, vec!["recipe_of_the_month".to_string()]);
+
+        let response = client
+            .post("/award-top-recipe")
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({
+                "category": "month",
+                "user_id": user_oid.to_hex(),
+                "period": "2025-06"
+            }))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["win_count"], 2);
+    }
+
+    async fn test_award_top_recipe_invalid_recipe_id<D: TestBackend>() {
+        let (client, db, _) = setup_test_client_with_backend::<D>().await;
+        let (user_oid, _) = insert_test_user(&db, vec![]);
+
+        let response = client
+            .post("/award-top-recipe")
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({
+                "category": "week",
+                "user_id": user_oid.to_hex(),
+                "recipe_id": "not_a_recipe"
+            }))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+        assert!(
+            body["message"]
+                .as_str()
+                .unwrap()
+                .contains("Invalid recipe ID format")
+        );
+    }
+
+    async fn test_award_top_recipe_invalid_period<D: TestBackend>() {
+        let (client, db, _) = setup_test_client_with_backend::<D>().await;
+        let (user_oid, _) = insert_test_user(&db, vec![]);
+
+        for period in ["x", "2025-W99", "2025-06"] {
+            let response = client
+                .post("/award-top-recipe")
+                .header("X-API-Key", get_test_api_key())
+                .json(&json!({
+                    "category": "week",
+                    "user_id": user_oid.to_hex(),
+                    "period": period
+                }))
+                .await;
+
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+            assert_eq!(
+                body["message"],
+                format!("Invalid period for category week: {}", period)
+            );
+        }
+
+        assert!(db.stored_user(&user_oid).await.badges.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_award_top_recipe_custom_category() {
+        dotenv::dotenv().ok();
+        let db = Arc::new(MockDatabase::default());
+        let notifier = Arc::new(MockNotifier::new());
+        let categories = CategoryRegistry::new(vec![CustomCategory {
+            key: "best_dessert_of_the_month".to_string(),
+            badge: "best_dessert_of_the_month".to_string(),
+            period: Period::Month,
+        }])
+        .unwrap();
+        let state = AppState {
+            categories,
+            ..build_test_state(db.clone(), notifier.clone())
+        };
+        let client = TestClient::new(create_router(Arc::new(state)));
+        let (user_oid, _) = insert_test_user(&db, vec![]);
+
+        let response = client
+            .post("/award-top-recipe")
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({
+                "category": "best_dessert_of_the_month",
+                "user_id": user_oid.to_hex()
+            }))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["badge"], "best_dessert_of_the_month");
+        assert_eq!(body["period"], Period::Month.period_of(chrono::Utc::now()));
+
+        // Built-in categories keep working alongside custom ones
+        let response = client
+            .post("/award-top-recipe")
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({
+                "category": "year",
+                "user_id": user_oid.to_hex()
+            }))
+            .await;
+        let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+        assert_eq!(body["badge"], "recipe_of_the_year");
+    }
+
+    async fn test_verification_status<D: TestBackend>() {
+        let (client, db, _) = setup_test_client_with_backend::<D>().await;
+        let user = create_test_user(vec![]);
+        let user_oid = user._id;
+        db.seed_user(user, create_test_recipes(user_oid, 12, 1));
+
+        let response = client
+            .get(&format!("/verification/{}", user_oid.to_hex()))
+            .header("X-API-Key", get_test_api_key())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+        assert_eq!(body["verified"], false);
+        assert_eq!(body["eligible"], false);
+        assert_eq!(body["stats"]["num_recipes"], 12);
+        assert_eq!(body["stats"]["total_likes"], 12);
+        assert_eq!(
+            body["missing"],
+            json!([{ "criterion": "recipes", "required": 30, "actual": 12 }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verification_status_errors() {
+        let client = setup_test_client().await;
+
+        let response = client
+            .get("/verification/invalid_object_id")
+            .header("X-API-Key", get_test_api_key())
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = client
+            .get(&format!("/verification/{}", ObjectId::new().to_hex()))
+            .header("X-API-Key", get_test_api_key())
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = client
+            .get(&format!("/verification/{}", ObjectId::new().to_hex()))
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_database_failure_is_not_leaked() {
+        let (client, db, _) = setup_test_client_with_db().await;
+        let (user_id, _) = insert_test_user(&db, vec![]);
+
+        *db.find_user_error.lock().unwrap() = Some(Error::Transient(
+            "Database error: connection refused by mongo-0:27017".to_string(),
+        ));
+        let response = client
+            .get(&format!("/verification/{}", user_id.to_hex()))
+            .header("X-API-Key", get_test_api_key())
+            .await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = response.json().await;
+        assert_eq!(body["status"], "error");
+        assert!(!body["message"].as_str().unwrap().contains("mongo-0"));
+
+        *db.find_user_error.lock().unwrap() =
+            Some(Error::Permanent("Database error: bad query".to_string()));
+        let response = client
+            .get(&format!("/verification/{}", user_id.to_hex()))
+            .header("X-API-Key", get_test_api_key())
+            .await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body: serde_json::Value = response.json().await;
+        assert_eq!(body["message"], "Internal server error");
+    }
+
+    async fn test_admin_revoke_verification<D: TestBackend>() {
+        let (client, db, notifier) = setup_test_client_with_backend::<D>().await;
+        let mut user = create_test_user(vec![]);
+        user.verified = Some(true);
+        let (user_oid, email) = (user._id, user.email.clone().unwrap());
+        db.seed_user(user, vec![]);
+
+        let response = client
+            .post("/admin/verification")
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({
+                "user_id": user_oid.to_hex(),
+                "verified": false,
+                "reason": "Spam account"
+            }))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+        assert_eq!(body["status"], "success");
+        assert_eq!(body["locked"], true);
+        assert_eq!(body["updated_by"], "test");
+
+        let user = db.stored_user(&user_oid).await;
+        assert_eq!(user.verified, Some(false));
+        assert_eq!(user.verification_locked, Some(true));
+        assert_eq!(user.verification_reason.as_deref(), Some("Spam account"));
+        assert_eq!(user.verification_updated_by.as_deref(), Some("test"));
+
+        let notes = notifier.notifications.lock().unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].0, "UNVERIFIED");
+        assert_eq!(notes[0].1, email);
+        assert_eq!(notes[0].2["reason"], "Spam account");
+    }
+
+    async fn test_admin_grant_verification<D: TestBackend>() {
+        let (client, db, notifier) = setup_test_client_with_backend::<D>().await;
+        let (user_oid, _) = insert_test_user(&db, vec![]);
+
+        let response = client
+            .post("/admin/verification")
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({
+                "user_id": user_oid.to_hex(),
+                "verified": true,
+                "locked": false,
+                "reason": "Known chef"
+            }))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let user = db.stored_user(&user_oid).await;
+        assert_eq!(user.verified, Some(true));
+        assert_eq!(user.verification_locked, Some(false));
+        assert_eq!(notifier.notifications.lock().unwrap()[0].0, "VERIFIED");
+    }
+
+    async fn test_admin_verification_validation<D: TestBackend>() {
+        let (client, db, _) = setup_test_client_with_backend::<D>().await;
+        let (user_oid, _) = insert_test_user(&db, vec![]);
+
+        let response = client
+            .post("/admin/verification")
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({
+                "user_id": user_oid.to_hex(),
+                "verified": false,
+                "reason": "  "
+            }))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = client
+            .post("/admin/verification")
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({
+                "user_id": ObjectId::new().to_hex(),
+                "verified": false,
+                "reason": "Spam"
+            }))
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = client
+            .post("/admin/verification")
+            .json(&json!({
+                "user_id": user_oid.to_hex(),
+                "verified": false,
+                "reason": "Spam"
+            }))
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    fn insert_test_review<D: TestBackend>(db: &Arc<D>, user_id: ObjectId) -> ObjectId {
+        let review = BadgeReview {
+            _id: ObjectId::new(),
+            user_id,
+            badges: vec!["level_100".to_string(), "week_streak".to_string()],
+            verify: true,
+            signals: vec![AbuseSignal::LikeSpike { level_delta: 300 }],
+            score: 1,
+            status: ReviewStatus::Pending,
+            created_at: chrono::Utc::now(),
+            resolved_at: None,
+            resolved_by: None,
+        };
+        let review_id = review._id;
+        db.seed_review(review);
+        review_id
+    }
+
+    async fn test_pending_reviews<D: TestBackend>() {
+        let (client, db, _) = setup_test_client_with_backend::<D>().await;
+        let (user_oid, _) = insert_test_user(&db, vec![]);
+        insert_test_review(&db, user_oid);
+
+        let response = client
+            .get("/admin/reviews")
+            .header("X-API-Key", get_test_api_key())
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+        assert_eq!(body["pending_count"], 1);
+        assert_eq!(body["reviews"][0]["status"], "pending");
+        assert_eq!(body["reviews"][0]["signals"][0]["signal"], "like_spike");
+    }
+
+    async fn test_approve_review_grants_held_awards<D: TestBackend>() {
+        let (client, db, notifier) = setup_test_client_with_backend::<D>().await;
+        let (user_oid, _) = insert_test_user(&db, vec!["level_100".to_string()]);
+        let review_id = insert_test_review(&db, user_oid);
+
+        let response = client
+            .post(&format!("/admin/reviews/{}", review_id.to_hex()))
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({ "action": "approve" }))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+        assert_eq!(body["granted_badges"], json!(["week_streak"]));
+        assert_eq!(body["verified"], true);
+        assert_eq!(body["review"]["status"], "approved");
+        assert_eq!(body["review"]["resolvedBy"], "test");
+
+        let user = db.stored_user(&user_oid).await;
+        assert!(user.badges.contains(&"week_streak".to_string()));
+        assert_eq!(user.verified, Some(true));
+        assert_eq!(user.verification_updated_by.as_deref(), Some("test"));
+
+        let kinds: Vec<String> = notifier
+            .notifications
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(kind, _, _)| kind.clone())
+            .collect();
+        assert_eq!(kinds, vec!["NEW_BADGE", "VERIFIED"]);
+
+        // Resolving twice is not possible
+        let response = client
+            .post(&format!("/admin/reviews/{}", review_id.to_hex()))
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({ "action": "reject" }))
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    async fn test_reject_review<D: TestBackend>() {
+        let (client, db, notifier) = setup_test_client_with_backend::<D>().await;
+        let (user_oid, _) = insert_test_user(&db, vec![]);
+        let review_id = insert_test_review(&db, user_oid);
+
+        let response = client
+            .post(&format!("/admin/reviews/{}", review_id.to_hex()))
+            .header("X-API-Key", get_test_api_key())
+            .json(&json!({ "action": "reject" }))
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&response.text().await).unwrap();
+        assert_eq!(body["review"]["status"], "rejected");
+        assert!(!body["review"]["resolvedAt"].is_null());
+        assert_eq!(body["review"]["resolvedBy"], "test");
+
+        let user = db.stored_user(&user_oid).await;
+        assert!(user.badges.is_empty());
+        assert_eq!(user.verified, Some(false));
+        assert!(notifier.notifications.lock().unwrap().is_empty());
+        assert!(db.get_pending_badge_reviews().await.unwrap().is_empty());
+
+        // The decision is stored with the key that made it
+        let reviews = db.get_user_badge_reviews(&user_oid).await.unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].status, ReviewStatus::Rejected);
+        assert_eq!(reviews[0].resolved_by.as_deref(), Some("test"));
+    }
+
+    crate::backend_tests!(
+        test_award_top_recipe_success,
+        test_award_top_recipe_counts_multiple_wins,
+        test_award_top_recipe_counts_legacy_badge,
+        test_award_top_recipe_invalid_recipe_id,
+        test_award_top_recipe_invalid_period,
+        test_verification_status,
+        test_admin_revoke_verification,
+        test_admin_grant_verification,
+        test_admin_verification_validation,
+        test_pending_reviews,
+        test_approve_review_grants_held_awards,
+        test_reject_review,
+    );
 }
@@ -1 +1,5 @@
+pub mod auth_tests;
+pub mod batch_tests;
 pub mod endpoints;
+pub mod rate_limit_tests;
+pub mod signature_tests;
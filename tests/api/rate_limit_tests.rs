@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{MockDatabase, MockNotifier, build_test_state, test_api_key};
+    use axum::http::StatusCode;
+    use axum::test_helpers::{TestClient, TestResponse};
+    use badge_forge::api::{route::create_router, state::AppState};
+    use badge_forge::middleware::rate_limit::{
+        MAX_BUCKETS, RateLimitConfig, RateLimiter, RateLimits,
+    };
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::queue::InMemoryQueue;
+    use mongodb::bson::oid::ObjectId;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+    use tokio::sync::mpsc;
+
+    fn client(
+        queue_size: usize,
+        config: RateLimitConfig,
+    ) -> (TestClient, mpsc::Receiver<LevelRequest>) {
+        let (queue, receiver) = InMemoryQueue::new(queue_size);
+        let state = AppState {
+            badge_queue: Arc::new(queue),
+            rate_limits: RateLimits::new(config),
+            ..build_test_state(Arc::new(MockDatabase::new()), Arc::new(MockNotifier::new()))
+        };
+        (TestClient::new(create_router(Arc::new(state))), receiver)
+    }
+
+    async fn update(client: &TestClient, user_id: &str) -> TestResponse {
+        client
+            .post("/update")
+            .header("X-API-Key", test_api_key())
+            .json(&json!({ "user_id": user_id }))
+            .await
+    }
+
+    #[test]
+    fn test_rate_limiter_refills() {
+        let limiter = RateLimiter::new(60);
+        let start = Instant::now();
+        for _ in 0..60 {
+            limiter.check("jorbites", start).unwrap();
+        }
+        let retry_after = limiter.check("jorbites", start).unwrap_err();
+        assert!(retry_after <= Duration::from_secs(1), "{:?}", retry_after);
+
+        // Other keys have their own bucket
+        limiter.check("voting", start).unwrap();
+
+        limiter
+            .check("jorbites", start + Duration::from_secs(1))
+            .unwrap();
+        assert!(
+            limiter
+                .check("jorbites", start + Duration::from_secs(1))
+                .is_err()
+        );
+
+        // Unlimited when 0
+        let limiter = RateLimiter::new(0);
+        for _ in 0..1000 {
+            limiter.check("jorbites", start).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_rate_limiter_is_bounded_when_all_buckets_are_in_use() {
+        let limiter = RateLimiter::new(10);
+        let start = Instant::now();
+        for i in 0..MAX_BUCKETS {
+            limiter
+                .check(
+                    &format!("user{}", i),
+                    start + Duration::from_micros(i as u64),
+                )
+                .unwrap();
+        }
+        assert_eq!(limiter.tracked_keys(), MAX_BUCKETS);
+
+        // The least recently used tenth makes room for new keys
+        limiter
+            .check("new", start + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(limiter.tracked_keys(), MAX_BUCKETS - MAX_BUCKETS / 10 + 1);
+        for _ in 0..9 {
+            limiter
+                .check(&format!("user{}", MAX_BUCKETS - 1), start)
+                .unwrap();
+        }
+        assert!(
+            limiter
+                .check(&format!("user{}", MAX_BUCKETS - 1), start)
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_rejects_updates() {
+        let (client, _receiver) = client(1, RateLimitConfig::default());
+
+        let response = update(&client, &ObjectId::new().to_hex()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The handler returns at once instead of waiting for room
+        let response = tokio::time::timeout(
+            Duration::from_secs(1),
+            update(&client, &ObjectId::new().to_hex()),
+        )
+        .await
+        .expect("/update should not block on a full queue");
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["Retry-After"], "5");
+    }
+
+    #[tokio::test]
+    async fn test_updates_limited_per_user() {
+        let (client, _receiver) = client(
+            10,
+            RateLimitConfig {
+                per_key: 0,
+                per_user: 2,
+            },
+        );
+        let user_id = ObjectId::new().to_hex();
+
+        for _ in 0..2 {
+            assert_eq!(update(&client, &user_id).await.status(), StatusCode::OK);
+        }
+        let response = update(&client, &user_id).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("Retry-After"));
+        let body: serde_json::Value = response.json().await;
+        assert_eq!(
+            body["message"],
+            format!("Too many badge updates requested for user {}", user_id)
+        );
+
+        let response = update(&client, &ObjectId::new().to_hex()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_requests_limited_per_key() {
+        let (client, _receiver) = client(
+            10,
+            RateLimitConfig {
+                per_key: 2,
+                per_user: 0,
+            },
+        );
+        let status = || client.get("/status").header("X-API-Key", test_api_key());
+
+        for _ in 0..2 {
+            assert_eq!(status().await.status(), StatusCode::OK);
+        }
+        let response = status().await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["Retry-After"], "30");
+
+        // Public routes and unauthenticated requests are not counted
+        assert_eq!(client.get("/health").await.status(), StatusCode::OK);
+        let response = client.get("/status").header("X-API-Key", "wrong").await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{MockDatabase, MockNotifier, build_test_state};
+    use axum::http::StatusCode;
+    use axum::test_helpers::{RequestBuilder, TestClient};
+    use badge_forge::api::{route::create_router, state::AppState};
+    use badge_forge::client::RequestSigner;
+    use badge_forge::middleware::auth::{ApiKey, ApiKeys, Scope};
+    use badge_forge::middleware::signature::{SignatureConfig, SignatureVerifier};
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::queue::InMemoryQueue;
+    use chrono::Utc;
+    use mongodb::bson::oid::ObjectId;
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    fn client(config: SignatureConfig) -> (TestClient, mpsc::Receiver<LevelRequest>) {
+        let (queue, receiver) = InMemoryQueue::new(10);
+        let state = AppState {
+            api_keys: ApiKeys::new(vec![
+                ApiKey {
+                    name: "jorbites".to_string(),
+                    key: "enqueue-secret".to_string(),
+                    scopes: vec![Scope::Enqueue],
+                    expires_at: None,
+                },
+                ApiKey::with_all_scopes("admin", "admin-secret"),
+            ])
+            .unwrap(),
+            signatures: SignatureVerifier::new(config),
+            badge_queue: Arc::new(queue),
+            ..build_test_state(Arc::new(MockDatabase::new()), Arc::new(MockNotifier::new()))
+        };
+        (TestClient::new(create_router(Arc::new(state))), receiver)
+    }
+
+    fn with_headers(mut request: RequestBuilder, headers: Vec<(&str, String)>) -> RequestBuilder {
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        request
+    }
+
+    fn update_body() -> Vec<u8> {
+        format!(r#"{{"user_id":"{}"}}"#, ObjectId::new().to_hex()).into_bytes()
+    }
+
+    #[tokio::test]
+    async fn test_signed_requests() {
+        let (client, _receiver) = client(SignatureConfig::default());
+        let signer = RequestSigner::new("jorbites", "enqueue-secret");
+        let body = update_body();
+        let post = || {
+            client
+                .post("/update")
+                .header("Content-Type", "application/json")
+        };
+
+        let headers = signer.signed_headers("POST", "/update", &body);
+        let response = with_headers(post(), headers.clone())
+            .body(body.clone())
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The same signature is not accepted twice
+        let response = with_headers(post(), headers).body(body.clone()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.text().await, "Request was already processed");
+
+        // A body other than the signed one
+        let headers = signer.signed_headers("POST", "/update", &body);
+        let response = with_headers(post(), headers).body(update_body()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.text().await, "Invalid signature");
+
+        // A secret other than the one of the named key
+        let headers =
+            RequestSigner::new("jorbites", "admin-secret").signed_headers("POST", "/update", &body);
+        let response = with_headers(post(), headers).body(body.clone()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Scopes still apply to signed requests
+        let headers = signer.signed_headers("GET", "/status", b"");
+        let response = with_headers(client.get("/status"), headers).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Plain API keys keep working unless signatures are required
+        let response = client
+            .get("/status")
+            .header("X-API-Key", "admin-secret")
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_replay_window_and_required_signatures() {
+        let (client, _receiver) = client(SignatureConfig {
+            required: true,
+            ..SignatureConfig::default()
+        });
+        let signer = RequestSigner::new("admin", "admin-secret");
+        let now = Utc::now().timestamp();
+
+        let headers = signer.signed_headers_at("GET", "/status", b"", now - 600, "stale");
+        let response = with_headers(client.get("/status"), headers).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.text().await,
+            "Request timestamp outside the allowed window"
+        );
+
+        // Extreme timestamps are rejected without overflowing
+        for timestamp in [i64::MIN, i64::MAX] {
+            let headers = signer.signed_headers_at("GET", "/status", b"", timestamp, "extreme");
+            let response = with_headers(client.get("/status"), headers).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        let verifier = SignatureVerifier::new(SignatureConfig::default());
+        assert!(!verifier.is_fresh(i64::MIN, Utc::now()));
+        assert!(!verifier.is_fresh(i64::MAX, Utc::now()));
+
+        let headers = signer.signed_headers_at("GET", "/status", b"", now - 60, "recent");
+        let response = with_headers(client.get("/status"), headers).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = client
+            .get("/status")
+            .header("X-API-Key", "admin-secret")
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.text().await, "Signed request required");
+
+        // Public routes need no signature
+        let response = client.get("/health").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
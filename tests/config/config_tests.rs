@@ -0,0 +1,232 @@
+#[cfg(test)]
+mod tests {
+    use badge_forge::config::{Config, ConfigSource, DEV_API_KEY};
+    use badge_forge::middleware::auth::Scope;
+    use badge_forge::model::category::Category;
+    use chrono::{TimeZone, Utc};
+    use std::time::Duration;
+
+    fn load(toml: &str) -> Result<Config, String> {
+        let source = ConfigSource::from_toml(toml)?;
+        let config = Config::from_source(&source)?;
+        source.check_unknown_keys()?;
+        Ok(config)
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = load("api_key = \"s3cret\"").unwrap();
+        assert_eq!(key_names(&config), ["default"]);
+        assert!(!config.insecure_dev_mode);
+        assert_eq!(config.queue_size, 100);
+        assert_eq!(config.batch_max_size, 100);
+        assert_eq!(config.worker_count, 1);
+        assert!(!config.cache.is_enabled());
+        assert!(config.axiom.is_none());
+    }
+
+    #[test]
+    fn test_settings_from_file() {
+        let config = load(
+            r#"
+            api_key = "s3cret"
+            queue_size = 250
+            worker_count = 4
+            db_cache_ttl_secs = 5
+            abuse_detection_enabled = true
+            shutdown_timeout_secs = 10
+
+            [[top_recipe_categories]]
+            key = "day"
+            badge = "recipe_of_the_day"
+            period = "day"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.queue_size, 250);
+        assert_eq!(config.batch_max_size, 250);
+        assert_eq!(config.worker_count, 4);
+        assert_eq!(config.cache.ttl, Duration::from_secs(5));
+        assert!(config.abuse.enabled);
+        assert_eq!(config.shutdown.timeout, Duration::from_secs(10));
+        assert!(matches!(
+            config.categories.parse("day"),
+            Some(Category::Custom(category)) if category.badge == "recipe_of_the_day"
+        ));
+    }
+
+    #[test]
+    fn test_env_override_of_file_key() {
+        // A name no other setting reads, so parallel tests are unaffected
+        const NAME: &str = "BADGE_FORGE_TEST_ENV_OVERRIDE";
+        // SAFETY: the environment is only accessed through std, which serializes access
+        unsafe { std::env::set_var(NAME, "from_env") };
+        let source =
+            ConfigSource::from_toml("badge_forge_test_env_override = \"from_file\"").unwrap();
+
+        assert_eq!(source.var(NAME).as_deref(), Some("from_env"));
+        assert_eq!(source.check_unknown_keys(), Ok(()));
+        unsafe { std::env::remove_var(NAME) };
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        let cases = [
+            ("worker_count = 0", "WORKER_COUNT must be at least 1"),
+            ("batch_max_size = 0", "BATCH_MAX_SIZE must be at least 1"),
+            (
+                "batch_max_size = 101",
+                "BATCH_MAX_SIZE (101) must not exceed QUEUE_SIZE (100)",
+            ),
+            (
+                "signature_max_age_secs = 0",
+                "SIGNATURE_MAX_AGE_SECS must be at least 1",
+            ),
+            (
+                "queue_size = -1",
+                "QUEUE_SIZE must be a non-negative integer",
+            ),
+            (
+                "abuse_detection_enabled = \"maybe\"",
+                "ABUSE_DETECTION_ENABLED must be true or false",
+            ),
+            (
+                "metrics_port = 70000",
+                "METRICS_PORT must be a valid port number",
+            ),
+            (
+                "axiom_token = \"secret\"",
+                "AXIOM_TOKEN and AXIOM_DATASET must be set together",
+            ),
+            (
+                "database_backend = \"postgres\"",
+                "Invalid DATABASE_BACKEND",
+            ),
+            ("worker_cuont = 2", "Unknown settings: worker_cuont"),
+            ("queue_size = ", "Invalid TOML"),
+        ];
+        for (toml, expected) in cases {
+            let error = load(&format!("api_key = \"s3cret\"\n{}", toml)).unwrap_err();
+            assert!(
+                error.contains(expected),
+                "{:?} failed with {:?}, expected {:?}",
+                toml,
+                error,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_api_key_required() {
+        let error = load("").unwrap_err();
+        assert!(error.contains("No API key is set"), "{}", error);
+
+        let error = load("api_key = \"default_key\"").unwrap_err();
+        assert!(
+            error.contains("must not be the publicly known"),
+            "{}",
+            error
+        );
+
+        let config = load("insecure_dev_mode = true").unwrap();
+        assert!(config.insecure_dev_mode);
+        assert_eq!(key_names(&config), ["dev"]);
+        assert!(
+            config
+                .api_keys
+                .authenticate(DEV_API_KEY, Utc::now())
+                .is_some()
+        );
+
+        // A real key is still used in dev mode
+        let config = load("insecure_dev_mode = true\napi_key = \"s3cret\"").unwrap();
+        assert_eq!(key_names(&config), ["default"]);
+    }
+
+    #[test]
+    fn test_named_api_keys() {
+        let config = load(
+            r#"
+            [[api_keys]]
+            name = "jorbites"
+            key = "new-secret"
+            scopes = ["enqueue"]
+
+            [[api_keys]]
+            name = "jorbites"
+            key = "old-secret"
+            scopes = ["enqueue"]
+            expires_at = 2026-11-01T00:00:00Z
+
+            [[api_keys]]
+            name = "voting"
+            key = "voting-secret"
+            scopes = ["award", "read"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(key_names(&config), ["jorbites", "jorbites", "voting"]);
+
+        let before = Utc.with_ymd_and_hms(2026, 10, 31, 0, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 11, 2, 0, 0, 0).unwrap();
+        let keys = &config.api_keys;
+        assert_eq!(
+            keys.authenticate("new-secret", after).unwrap().name,
+            "jorbites"
+        );
+        assert_eq!(
+            keys.authenticate("old-secret", before).unwrap().name,
+            "jorbites"
+        );
+        assert!(keys.authenticate("old-secret", after).is_none());
+        assert_eq!(
+            keys.authenticate("voting-secret", after).unwrap().scopes,
+            [Scope::Award, Scope::Read]
+        );
+        assert!(keys.authenticate("unknown", after).is_none());
+        assert!(keys.authenticate("new-secre", after).is_none());
+        assert!(keys.authenticate("new-secret2", after).is_none());
+        assert!(keys.authenticate("", after).is_none());
+    }
+
+    #[test]
+    fn test_invalid_api_keys() {
+        let cases = [
+            (
+                r#"api_keys = [{ name = "job", key = "a", scopes = ["write"] }]"#,
+                "Invalid API_KEYS",
+            ),
+            (
+                r#"api_keys = [{ name = "job", key = "a", scopes = [] }]"#,
+                "API key job has no scopes",
+            ),
+            (
+                r#"api_keys = [{ name = "a", key = "k", scopes = ["read"] }, { name = "b", key = "k", scopes = ["read"] }]"#,
+                "API key b reuses the key of another entry",
+            ),
+            (
+                r#"api_keys = [{ name = "job", key = "default_key", scopes = ["read"] }]"#,
+                "API key job must not be the publicly known",
+            ),
+        ];
+        for (toml, expected) in cases {
+            let error = load(toml).unwrap_err();
+            assert!(
+                error.contains(expected),
+                "{:?} failed with {:?}",
+                toml,
+                error
+            );
+        }
+    }
+
+    fn key_names(config: &Config) -> Vec<&str> {
+        config
+            .api_keys
+            .iter()
+            .map(|key| key.name.as_str())
+            .collect()
+    }
+}
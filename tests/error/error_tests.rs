@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use badge_forge::error::Error;
+    use std::time::Duration;
+
+    #[test]
+    fn test_status_codes() {
+        let cases = [
+            (
+                Error::NotFound("User not found".into()),
+                StatusCode::NOT_FOUND,
+            ),
+            (Error::InvalidId("bad id".into()), StatusCode::BAD_REQUEST),
+            (
+                Error::Validation("bad input".into()),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                Error::Transient("timeout".into()),
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            (
+                Error::Permanent("bad query".into()),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+            (Error::Conflict("changed".into()), StatusCode::CONFLICT),
+            (
+                Error::TooManyRequests {
+                    message: "slow down".into(),
+                    retry_after: Duration::from_secs(5),
+                },
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+        ];
+
+        for (error, status) in cases {
+            assert_eq!(error.status_code(), status, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn test_retry_decisions() {
+        assert!(Error::Transient("timeout".into()).is_retryable());
+        assert!(Error::Conflict("changed".into()).is_retryable());
+        assert!(!Error::Permanent("bad query".into()).is_retryable());
+        assert!(!Error::NotFound("User not found".into()).is_retryable());
+        assert!(!Error::InvalidId("bad id".into()).is_retryable());
+    }
+
+    #[test]
+    fn test_too_many_requests_sets_retry_after() {
+        let response = Error::TooManyRequests {
+            message: "slow down".into(),
+            retry_after: Duration::from_millis(2500),
+        }
+        .into_response();
+        assert_eq!(response.headers()["Retry-After"], "3");
+
+        let response = Error::TooManyRequests {
+            message: "slow down".into(),
+            retry_after: Duration::ZERO,
+        }
+        .into_response();
+        assert_eq!(response.headers()["Retry-After"], "1");
+    }
+
+    #[test]
+    fn test_public_message_hides_infrastructure_details() {
+        let error = Error::Transient("Database error: connection reset by 10.0.0.5".into());
+        assert!(!error.public_message().contains("10.0.0.5"));
+
+        let error = Error::Permanent("Database error: unknown operator $foo".into());
+        assert_eq!(error.public_message(), "Internal server error");
+
+        let error = Error::NotFound("User not found: abc".into());
+        assert_eq!(error.public_message(), "User not found: abc");
+    }
+
+    #[test]
+    fn test_from_mongodb_error() {
+        let io_error = mongodb::error::Error::from(std::io::Error::new(
+            std::io::ErrorKind::ConnectionReset,
+            "connection reset",
+        ));
+        assert!(matches!(Error::from(io_error), Error::Transient(_)));
+
+        let custom_error = mongodb::error::Error::custom("unexpected");
+        assert!(matches!(Error::from(custom_error), Error::Permanent(_)));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod api;
+pub mod config;
+pub mod error;
 pub mod queue;
 pub mod service;
 pub mod utils;
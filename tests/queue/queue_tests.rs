@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use badge_forge::{
+        error::Error,
         model::level::LevelRequest,
         queue::{BadgeUpdateQueue, InMemoryQueue},
     };
@@ -290,6 +291,41 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_requests_are_pending_before_they_are_received() {
+        let (queue, mut receiver) = InMemoryQueue::new(10);
+        let queue = Arc::new(queue);
+
+        // The processor removes each request as soon as it receives it
+        let consumer_queue = queue.clone();
+        let consumer = tokio::spawn(async move {
+            for _ in 0..200 {
+                let request = receiver.recv().await.unwrap();
+                let pending = consumer_queue.get_pending_requests().await;
+                assert!(
+                    pending.iter().any(|r| r.request_id == request.request_id),
+                    "received a request that was not pending"
+                );
+                consumer_queue.remove_request(&request.request_id).await;
+            }
+        });
+
+        for i in 0..200 {
+            let request = create_test_request(&format!("user{}", i));
+            if i % 2 == 0 {
+                queue.enqueue(request).await.unwrap();
+            } else {
+                while let Err(Error::TooManyRequests { .. }) =
+                    queue.try_enqueue(request.clone()).await
+                {
+                    tokio::task::yield_now().await;
+                }
+            }
+        }
+        consumer.await.unwrap();
+        assert!(queue.get_pending_requests().await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_queue_backpressure() {
         // Create a very small buffer to test backpressure
@@ -315,6 +351,13 @@ mod tests {
             "Enqueue should block when buffer is full"
         );
 
+        // The non-blocking variant fails right away instead
+        let result = queue.try_enqueue(create_test_request("user4")).await;
+        assert!(
+            matches!(result, Err(Error::TooManyRequests { .. })),
+            "try_enqueue should reject when buffer is full"
+        );
+
         // Verify we still have the first two in the pending list
         let pending = queue.get_pending_requests().await;
         assert_eq!(
@@ -324,6 +367,40 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_try_enqueue() {
+        let (queue, mut receiver) = InMemoryQueue::new(1);
+
+        queue
+            .try_enqueue(create_empty_request("user1"))
+            .await
+            .unwrap();
+        let pending = queue.get_pending_requests().await;
+        assert_eq!(pending.len(), 1);
+        assert!(!pending[0].request_id.is_empty());
+
+        // Room is made as soon as the processor takes the request
+        receiver.recv().await.unwrap();
+        queue
+            .try_enqueue(create_test_request("user2"))
+            .await
+            .unwrap();
+
+        drop(receiver);
+        let result = queue.try_enqueue(create_test_request("user3")).await;
+        assert!(matches!(result, Err(Error::Permanent(_))));
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_after_receiver_dropped() {
+        let (queue, receiver) = InMemoryQueue::new(2);
+        drop(receiver);
+
+        let result = queue.enqueue(create_test_request("user1")).await;
+        assert!(matches!(result, Err(Error::Permanent(_))));
+        assert!(queue.get_pending_requests().await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_same_user_different_requests() {
         let (queue, _receiver) = InMemoryQueue::new(10);
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use badge_forge::{
+        model::{recipe::Recipe, review::AbuseSignal},
+        service::abuse::AbuseDetector,
+    };
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use mongodb::bson::oid::ObjectId;
+
+    fn enabled_detector() -> AbuseDetector {
+        AbuseDetector {
+            enabled: true,
+            ..AbuseDetector::default()
+        }
+    }
+
+    fn create_recipe(likes: i32, created_at: DateTime<Utc>) -> Recipe {
+        Recipe {
+            _id: ObjectId::new(),
+            user_id: ObjectId::new(),
+            num_likes: likes,
+            created_at,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 30, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_disabled_detector_never_flags() {
+        let recipes: Vec<Recipe> = (0..10)
+            .map(|i| create_recipe(100, now() - Duration::seconds(i)))
+            .collect();
+
+        let report = AbuseDetector::default().evaluate(&recipes, 1, 5000, now());
+        assert!(!report.flagged);
+        assert_eq!(report.score, 0);
+        assert!(report.signals.is_empty());
+    }
+
+    #[test]
+    fn test_regular_activity_is_not_flagged() {
+        let recipes: Vec<Recipe> = (0..20)
+            .map(|i| create_recipe(5, now() - Duration::days(i * 3 + 2)))
+            .collect();
+
+        let report = enabled_detector().evaluate(&recipes, 100, 120, now());
+        assert!(!report.flagged);
+        assert!(report.signals.is_empty());
+    }
+
+    #[test]
+    fn test_recipe_burst() {
+        let start = now() - Duration::days(30);
+        let recipes: Vec<Recipe> = (0..6)
+            .map(|i| create_recipe(0, start + Duration::seconds(i * 5)))
+            .collect();
+
+        let report = enabled_detector().evaluate(&recipes, 0, 6, now());
+        assert!(report.flagged);
+        assert_eq!(
+            report.signals,
+            vec![AbuseSignal::RecipeBurst {
+                recipes: 6,
+                window_secs: 60
+            }]
+        );
+    }
+
+    #[test]
+    fn test_likes_on_new_recipes() {
+        let recipes = vec![
+            create_recipe(5, now() - Duration::days(100)),
+            create_recipe(95, now() - Duration::hours(2)),
+        ];
+
+        let report = enabled_detector().evaluate(&recipes, 0, 102, now());
+        assert!(report.flagged);
+        assert_eq!(
+            report.signals,
+            vec![AbuseSignal::LikesOnNewRecipes {
+                likes: 95,
+                share_percent: 95
+            }]
+        );
+
+        // Too few likes to be meaningful
+        let recipes = vec![create_recipe(10, now() - Duration::hours(2))];
+        let report = enabled_detector().evaluate(&recipes, 0, 11, now());
+        assert!(!report.flagged);
+    }
+
+    #[test]
+    fn test_like_spike() {
+        let recipes = vec![create_recipe(300, now() - Duration::days(100))];
+
+        let report = enabled_detector().evaluate(&recipes, 50, 301, now());
+        assert!(report.flagged);
+        assert_eq!(
+            report.signals,
+            vec![AbuseSignal::LikeSpike { level_delta: 251 }]
+        );
+
+        // Users that were never computed are not checked for spikes
+        let report = enabled_detector().evaluate(&recipes, 0, 301, now());
+        assert!(!report.flagged);
+    }
+
+    #[test]
+    fn test_flag_score_threshold() {
+        let detector = AbuseDetector {
+            flag_score: 2,
+            ..enabled_detector()
+        };
+        let recipes = vec![create_recipe(300, now() - Duration::days(100))];
+
+        let report = detector.evaluate(&recipes, 50, 301, now());
+        assert_eq!(report.score, 1);
+        assert!(!report.flagged);
+    }
+}
@@ -0,0 +1,355 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{
+        MockDatabase, MockNotifier, TestBackend, create_test_recipes, create_test_user,
+    };
+    use badge_forge::{
+        error::Error,
+        model::{level::LevelRequest, review::ReviewStatus},
+        service::{
+            abuse::AbuseDetector, badge_processor::BadgeForgeProcessor, db::Database,
+            notifier::Notifier, verification::VerificationPolicy,
+        },
+    };
+    use chrono::Utc;
+    use std::sync::Arc;
+    use std::sync::atomic::Ordering;
+
+    fn create_processor<D: Database + 'static>(
+        db: &Arc<D>,
+        notifier: &Arc<MockNotifier>,
+    ) -> BadgeForgeProcessor {
+        BadgeForgeProcessor::new(
+            db.clone() as Arc<dyn Database>,
+            notifier.clone() as Arc<dyn Notifier>,
+        )
+    }
+
+    fn create_request(user_id: &str) -> LevelRequest {
+        LevelRequest {
+            user_id: user_id.to_string(),
+            request_id: uuid::Uuid::new_v4().to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    async fn test_process_request_updates_level_and_badges<D: TestBackend>() {
+        let db = Arc::new(D::create());
+        let notifier = Arc::new(MockNotifier::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.seed_user(user, create_test_recipes(user_id, 10, 10));
+
+        let processor = create_processor(&db, &notifier);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        let updated = db.stored_user(&user_id).await;
+        assert_eq!(updated.level, 110);
+        assert!(updated.badges.contains(&"level_100".to_string()));
+        assert!(updated.badges.contains(&"week_streak".to_string()));
+        assert_eq!(updated.verified, Some(false));
+    }
+
+    async fn test_process_request_unknown_user<D: TestBackend>() {
+        let db = Arc::new(D::create());
+        let notifier = Arc::new(MockNotifier::new());
+        let processor = create_processor(&db, &notifier);
+
+        let result = processor
+            .process_request(create_request(
+                &mongodb::bson::oid::ObjectId::new().to_hex(),
+            ))
+            .await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+
+        let result = processor.process_request(create_request("not_an_id")).await;
+        assert!(matches!(result, Err(Error::InvalidId(_))));
+    }
+
+    async fn test_process_request_verifies_with_default_policy<D: TestBackend>() {
+        let db = Arc::new(D::create());
+        let notifier = Arc::new(MockNotifier::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.seed_user(user, create_test_recipes(user_id, 30, 0));
+
+        let processor = create_processor(&db, &notifier);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        assert_eq!(db.stored_user(&user_id).await.verified, Some(true));
+        let notes = notifier.notifications.lock().unwrap();
+        assert!(notes.iter().any(|(kind, _, _)| kind == "VERIFIED"));
+    }
+
+    async fn test_process_request_uses_configured_policy<D: TestBackend>() {
+        let db = Arc::new(D::create());
+        let notifier = Arc::new(MockNotifier::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.seed_user(user, create_test_recipes(user_id, 30, 0));
+
+        let policy = VerificationPolicy {
+            min_total_likes: 10,
+            ..VerificationPolicy::default()
+        };
+        let processor = create_processor(&db, &notifier).with_verification_policy(policy);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        assert_eq!(db.stored_user(&user_id).await.verified, Some(false));
+        let notes = notifier.notifications.lock().unwrap();
+        assert!(!notes.iter().any(|(kind, _, _)| kind == "VERIFIED"));
+    }
+
+    async fn test_process_request_respects_verification_lock<D: TestBackend>() {
+        let db = Arc::new(D::create());
+        let notifier = Arc::new(MockNotifier::new());
+        let mut user = create_test_user(vec![]);
+        user.verification_locked = Some(true);
+        user.verification_reason = Some("Spam account".to_string());
+        let user_id = user._id;
+        db.seed_user(user, create_test_recipes(user_id, 40, 0));
+
+        let processor = create_processor(&db, &notifier);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        let updated = db.stored_user(&user_id).await;
+        assert_eq!(updated.verified, Some(false));
+        assert_eq!(updated.level, 40);
+        let notes = notifier.notifications.lock().unwrap();
+        assert!(!notes.iter().any(|(kind, _, _)| kind == "VERIFIED"));
+    }
+
+    async fn test_process_request_holds_badges_of_flagged_user<D: TestBackend>() {
+        let db = Arc::new(D::create());
+        let notifier = Arc::new(MockNotifier::new());
+        let mut user = create_test_user(vec![]);
+        user.level = 10;
+        let user_id = user._id;
+        db.seed_user(user, create_test_recipes(user_id, 30, 10));
+
+        let detector = AbuseDetector {
+            enabled: true,
+            ..AbuseDetector::default()
+        };
+        let processor = create_processor(&db, &notifier).with_abuse_detector(detector);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        // Level is updated, badges and verification are held
+        let updated = db.stored_user(&user_id).await;
+        assert_eq!(updated.level, 330);
+        assert!(updated.badges.is_empty());
+        assert_eq!(updated.verified, Some(false));
+        assert!(notifier.notifications.lock().unwrap().is_empty());
+
+        let reviews = db.get_pending_badge_reviews().await.unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].user_id, user_id);
+        assert!(reviews[0].verify);
+        assert!(reviews[0].badges.contains(&"level_250".to_string()));
+        assert!(reviews[0].badges.contains(&"week_streak".to_string()));
+        assert_eq!(reviews[0].score, 1);
+    }
+
+    async fn test_process_request_keeps_badges_held_until_review<D: TestBackend>() {
+        let db = Arc::new(D::create());
+        let notifier = Arc::new(MockNotifier::new());
+        let mut user = create_test_user(vec![]);
+        user.level = 10;
+        let user_id = user._id;
+        db.seed_user(user, create_test_recipes(user_id, 30, 10));
+
+        let detector = AbuseDetector {
+            enabled: true,
+            ..AbuseDetector::default()
+        };
+        let processor = create_processor(&db, &notifier).with_abuse_detector(detector);
+
+        // The level no longer jumps on later updates, which must not grant the held badges
+        for _ in 0..3 {
+            let outcome = processor
+                .process_request(create_request(&user_id.to_hex()))
+                .await
+                .unwrap();
+            assert!(outcome.badges_added.is_empty());
+            assert!(!outcome.newly_verified);
+            assert!(outcome.held_for_review);
+        }
+        let updated = db.stored_user(&user_id).await;
+        assert!(updated.badges.is_empty());
+        assert_eq!(updated.verified, Some(false));
+
+        // Nor does rejecting the review
+        let reviews = db.get_pending_badge_reviews().await.unwrap();
+        assert_eq!(reviews.len(), 1);
+        db.resolve_badge_review(&reviews[0]._id, ReviewStatus::Rejected, "moderation")
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            let outcome = processor
+                .process_request(create_request(&user_id.to_hex()))
+                .await
+                .unwrap();
+            assert!(outcome.badges_added.is_empty());
+            assert!(!outcome.held_for_review);
+        }
+
+        let updated = db.stored_user(&user_id).await;
+        assert!(updated.badges.is_empty());
+        assert_eq!(updated.verified, Some(false));
+        assert!(db.get_pending_badge_reviews().await.unwrap().is_empty());
+        assert!(notifier.notifications.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_process_request_skips_recipe_list_when_streaks_owned() {
+        let db = Arc::new(MockDatabase::new());
+        let notifier = Arc::new(MockNotifier::new());
+        let user = create_test_user(vec!["week_streak".to_string(), "month_streak".to_string()]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 10, 10));
+
+        let processor = create_processor(&db, &notifier);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        assert_eq!(db.recipe_list_calls.load(Ordering::SeqCst), 0);
+        let updated = db.get_user(&user_id);
+        assert_eq!(updated.level, 110);
+        assert!(updated.badges.contains(&"level_100".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_process_request_loads_recipe_list_for_missing_streaks() {
+        let db = Arc::new(MockDatabase::new());
+        let notifier = Arc::new(MockNotifier::new());
+        let user = create_test_user(vec!["week_streak".to_string()]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 28, 0));
+
+        let processor = create_processor(&db, &notifier);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        assert_eq!(db.recipe_list_calls.load(Ordering::SeqCst), 1);
+        assert!(
+            db.get_user(&user_id)
+                .badges
+                .contains(&"month_streak".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_award_is_not_lost() {
+        let db = Arc::new(MockDatabase::new());
+        let notifier = Arc::new(MockNotifier::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 10, 10));
+
+        // An /award-top-recipe write lands after the processor read the user
+        *db.after_find_user.lock().unwrap() = Some(Box::new(|user| {
+            user.badges.push("recipe_of_the_week".to_string());
+        }));
+
+        let processor = create_processor(&db, &notifier);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        let updated = db.get_user(&user_id);
+        assert_eq!(updated.level, 110);
+        assert!(updated.badges.contains(&"level_100".to_string()));
+        assert!(
+            updated.badges.contains(&"recipe_of_the_week".to_string()),
+            "Concurrently awarded badge was erased: {:?}",
+            updated.badges
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_level_change_is_retried() {
+        let db = Arc::new(MockDatabase::new());
+        let notifier = Arc::new(MockNotifier::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 10, 10));
+
+        // Another recomputation stores a level in between, invalidating the first attempt
+        *db.after_find_user.lock().unwrap() = Some(Box::new(|user| {
+            user.level = 50;
+        }));
+
+        let processor = create_processor(&db, &notifier);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        let updated = db.get_user(&user_id);
+        assert_eq!(updated.level, 110);
+
+        // Notifications are only sent for the attempt that was written
+        let notes = notifier.notifications.lock().unwrap();
+        let level_100_notes = notes
+            .iter()
+            .filter(|(_, _, metadata)| metadata["badgeName"] == "level_100")
+            .count();
+        assert_eq!(level_100_notes, 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_lock_prevents_verification() {
+        let db = Arc::new(MockDatabase::new());
+        let notifier = Arc::new(MockNotifier::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 30, 0));
+
+        // An admin revokes and locks verification while the processor computes
+        *db.after_find_user.lock().unwrap() = Some(Box::new(|user| {
+            user.verification_locked = Some(true);
+        }));
+
+        let processor = create_processor(&db, &notifier);
+        processor
+            .process_request(create_request(&user_id.to_hex()))
+            .await
+            .unwrap();
+
+        let updated = db.get_user(&user_id);
+        assert_eq!(updated.verified, Some(false));
+        assert_eq!(updated.level, 30);
+        let notes = notifier.notifications.lock().unwrap();
+        assert!(!notes.iter().any(|(kind, _, _)| kind == "VERIFIED"));
+    }
+
+    crate::backend_tests!(
+        test_process_request_updates_level_and_badges,
+        test_process_request_unknown_user,
+        test_process_request_verifies_with_default_policy,
+        test_process_request_uses_configured_policy,
+        test_process_request_respects_verification_lock,
+        test_process_request_holds_badges_of_flagged_user,
+        test_process_request_keeps_badges_held_until_review,
+    );
+}
@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{
+        MockDatabase, MockNotifier, build_test_state, create_test_recipes, create_test_user,
+    };
+    use axum::test_helpers::TestClient;
+    use badge_forge::api::{route::create_router, state::AppState};
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::service::badge_processor::BadgeForgeProcessor;
+    use badge_forge::service::db::{CacheConfig, CacheStats, CachedDatabase, Database};
+    use chrono::Utc;
+    use mongodb::bson::oid::ObjectId;
+    use std::sync::Arc;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    fn cached(ttl: Duration, max_users: usize) -> (Arc<MockDatabase>, CachedDatabase) {
+        let inner = Arc::new(MockDatabase::new());
+        let cache = CachedDatabase::new(inner.clone(), CacheConfig { ttl, max_users });
+        (inner, cache)
+    }
+
+    fn insert_user(db: &MockDatabase, recipes: usize) -> ObjectId {
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, recipes, 10));
+        user_id
+    }
+
+    #[tokio::test]
+    async fn test_repeated_reads_are_served_from_cache() {
+        let (inner, cache) = cached(Duration::from_secs(60), 100);
+        let user_id = insert_user(&inner, 3);
+
+        for _ in 0..3 {
+            assert_eq!(cache.get_user_recipes(&user_id).await.unwrap().len(), 3);
+            assert_eq!(cache.get_user_stats(&user_id).await.unwrap().num_recipes, 3);
+            assert!(cache.find_user(&user_id).await.unwrap().is_some());
+        }
+
+        assert_eq!(inner.recipe_list_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            cache.counters().snapshot(),
+            CacheStats {
+                hits: 6,
+                misses: 3,
+                ..CacheStats::default()
+            }
+        );
+
+        // Unknown users are looked up every time
+        let unknown = ObjectId::new();
+        assert!(cache.find_user(&unknown).await.unwrap().is_none());
+        inner.insert_user(create_test_user(vec![]), vec![]);
+        assert!(cache.find_user(&unknown).await.unwrap().is_none());
+        assert_eq!(cache.counters().snapshot().misses, 5);
+    }
+
+    #[tokio::test]
+    async fn test_writes_invalidate_cached_user() {
+        let (inner, cache) = cached(Duration::from_secs(60), 100);
+        let user_id = insert_user(&inner, 3);
+
+        cache.find_user(&user_id).await.unwrap();
+        cache.get_user_recipes(&user_id).await.unwrap();
+        cache
+            .add_badge_to_user(&user_id, "level_100")
+            .await
+            .unwrap();
+
+        let user = cache.find_user(&user_id).await.unwrap().unwrap();
+        assert_eq!(user.badges, vec!["level_100"]);
+        assert_eq!(cache.counters().snapshot().invalidations, 1);
+
+        // Recipe aggregates are kept, badge_forge never writes recipes
+        cache.get_user_recipes(&user_id).await.unwrap();
+        assert_eq!(inner.recipe_list_calls.load(Ordering::SeqCst), 1);
+
+        cache.invalidate(&user_id);
+        cache.get_user_recipes(&user_id).await.unwrap();
+        assert_eq!(inner.recipe_list_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_load_overlapping_invalidation_is_not_cached() {
+        let (inner, cache) = cached(Duration::from_secs(60), 100);
+        let user_id = insert_user(&inner, 3);
+        let cache = Arc::new(cache);
+        cache.get_user_stats(&user_id).await.unwrap();
+
+        // The user changes and is invalidated while its previous version is being loaded
+        let invalidating = cache.clone();
+        *inner.after_find_user.lock().unwrap() = Some(Box::new(move |user| {
+            user.level = 110;
+            invalidating.invalidate(&user_id);
+        }));
+        assert_eq!(cache.find_user(&user_id).await.unwrap().unwrap().level, 0);
+
+        // The stale load was not cached, so the next read sees the change
+        assert_eq!(cache.find_user(&user_id).await.unwrap().unwrap().level, 110);
+    }
+
+    #[tokio::test]
+    async fn test_entries_expire_and_are_evicted() {
+        let (inner, cache) = cached(Duration::from_millis(50), 2);
+        let users: Vec<_> = (0..3).map(|_| insert_user(&inner, 1)).collect();
+
+        cache.get_user_recipes(&users[0]).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        cache.get_user_recipes(&users[0]).await.unwrap();
+        assert_eq!(inner.recipe_list_calls.load(Ordering::SeqCst), 2);
+
+        // A third user evicts the entry closest to expiry
+        cache.get_user_recipes(&users[1]).await.unwrap();
+        cache.get_user_recipes(&users[2]).await.unwrap();
+        assert_eq!(cache.counters().snapshot().evictions, 1);
+        cache.get_user_recipes(&users[0]).await.unwrap();
+        assert_eq!(inner.recipe_list_calls.load(Ordering::SeqCst), 5);
+
+        // A full cache frees a tenth of its entries at once
+        let (inner, cache) = cached(Duration::from_secs(60), 20);
+        let users: Vec<_> = (0..21).map(|_| insert_user(&inner, 1)).collect();
+        for user_id in &users {
+            cache.get_user_recipes(user_id).await.unwrap();
+        }
+        assert_eq!(cache.counters().snapshot().evictions, 2);
+        // The next insert has room without another eviction
+        cache.get_user_recipes(&users[0]).await.unwrap();
+        cache.get_user_recipes(&users[2]).await.unwrap();
+        assert_eq!(cache.counters().snapshot().evictions, 2);
+        assert_eq!(inner.recipe_list_calls.load(Ordering::SeqCst), 22);
+    }
+
+    fn request(user_id: &ObjectId) -> LevelRequest {
+        LevelRequest {
+            user_id: user_id.to_hex(),
+            request_id: uuid::Uuid::new_v4().to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_processor_sees_its_own_writes_through_cache() {
+        let (inner, cache) = cached(Duration::from_secs(60), 100);
+        let user_id = insert_user(&inner, 10);
+        let db = Arc::new(cache);
+        let processor = BadgeForgeProcessor::new(db.clone(), Arc::new(MockNotifier::new()));
+
+        // A burst queued before the first is processed shares its aggregates
+        let requests = [request(&user_id), request(&user_id)];
+        for request in requests {
+            processor.process_request(request).await.unwrap();
+        }
+
+        // The second run reads the level written by the first, so it does not conflict
+        let user = db.find_user(&user_id).await.unwrap().unwrap();
+        assert_eq!(user.level, 110);
+        assert_eq!(inner.get_user(&user_id).level, 110);
+        assert_eq!(db.counters().snapshot().hits, 2);
+    }
+
+    #[tokio::test]
+    async fn test_processor_reloads_aggregates_older_than_request() {
+        let (inner, cache) = cached(Duration::from_secs(60), 100);
+        let user_id = insert_user(&inner, 10);
+        let db = Arc::new(cache);
+        let processor = BadgeForgeProcessor::new(db.clone(), Arc::new(MockNotifier::new()));
+        processor.process_request(request(&user_id)).await.unwrap();
+
+        // Likes arrive after the aggregates were cached, then their update is requested
+        inner
+            .recipes
+            .lock()
+            .unwrap()
+            .insert(user_id, create_test_recipes(user_id, 10, 20));
+        let outcome = processor.process_request(request(&user_id)).await.unwrap();
+
+        assert_eq!(outcome.level, 210);
+        assert_eq!(inner.get_user(&user_id).level, 210);
+        assert_eq!(inner.recipe_list_calls.load(Ordering::SeqCst), 2);
+
+        // Aggregates loaded after a request was created are still reused
+        db.expire_aggregates(&user_id, Utc::now() - chrono::Duration::seconds(1));
+        db.get_user_recipes(&user_id).await.unwrap();
+        assert_eq!(inner.recipe_list_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_status_reports_cache_stats() {
+        let (inner, cache) = cached(Duration::from_secs(60), 100);
+        let user_id = insert_user(&inner, 1);
+        cache.get_user_stats(&user_id).await.unwrap();
+        cache.get_user_stats(&user_id).await.unwrap();
+
+        let state = AppState {
+            cache: Some(cache.counters()),
+            ..build_test_state(inner, Arc::new(MockNotifier::new()))
+        };
+        let client = TestClient::new(create_router(Arc::new(state)));
+        dotenv::dotenv().ok();
+        let api_key = std::env::var("API_KEY").unwrap_or_else(|_| "default_key".to_string());
+        let response = client.get("/status").header("X-API-Key", api_key).await;
+        let body: serde_json::Value = response.json().await;
+        assert_eq!(body["cache"]["hits"], 1);
+        assert_eq!(body["cache"]["misses"], 1);
+    }
+}
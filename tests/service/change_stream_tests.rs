@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod tests {
+    use badge_forge::{
+        queue::{BadgeUpdateQueue, InMemoryQueue},
+        service::change_stream::{
+            PreImages, RecipeWatcher, is_history_lost, level_request_from_event,
+        },
+        service::schema::SchemaMapping,
+    };
+    use mongodb::bson::{Document, doc, oid::ObjectId};
+    use mongodb::change_stream::event::ChangeStreamEvent;
+    use mongodb::error::{CommandError, ErrorKind};
+    use std::{sync::Arc, time::Duration};
+
+    fn event(fields: Document) -> ChangeStreamEvent<Document> {
+        let mut event = doc! { "_id": { "_data": "826500000000000000" } };
+        event.extend(fields);
+        mongodb::bson::from_document(event).unwrap()
+    }
+
+    fn recipe(user_id: ObjectId) -> Document {
+        doc! { "_id": ObjectId::new(), "userId": user_id, "numLikes": 3 }
+    }
+
+    #[test]
+    fn test_insert_event() {
+        let user_id = ObjectId::new();
+        let event = event(doc! {
+            "operationType": "insert",
+            "fullDocument": recipe(user_id)
+        });
+
+        let request = level_request_from_event(&event, &SchemaMapping::default()).unwrap();
+        assert_eq!(request.user_id, user_id.to_hex());
+        assert!(!request.request_id.is_empty());
+    }
+
+    #[test]
+    fn test_update_event_requires_likes_change() {
+        let user_id = ObjectId::new();
+        let likes_update = event(doc! {
+            "operationType": "update",
+            "updateDescription": { "updatedFields": { "numLikes": 4 }, "removedFields": [] },
+            "fullDocument": recipe(user_id)
+        });
+        assert_eq!(
+            level_request_from_event(&likes_update, &SchemaMapping::default())
+                .unwrap()
+                .user_id,
+            user_id.to_hex()
+        );
+
+        let title_update = event(doc! {
+            "operationType": "update",
+            "updateDescription": { "updatedFields": { "title": "Paella" }, "removedFields": [] },
+            "fullDocument": recipe(user_id)
+        });
+        assert!(level_request_from_event(&title_update, &SchemaMapping::default()).is_none());
+    }
+
+    #[test]
+    fn test_delete_event_uses_pre_image() {
+        let user_id = ObjectId::new();
+        let with_pre_image = event(doc! {
+            "operationType": "delete",
+            "fullDocumentBeforeChange": recipe(user_id)
+        });
+        assert_eq!(
+            level_request_from_event(&with_pre_image, &SchemaMapping::default())
+                .unwrap()
+                .user_id,
+            user_id.to_hex()
+        );
+
+        let without_pre_image = event(doc! { "operationType": "delete" });
+        assert!(level_request_from_event(&without_pre_image, &SchemaMapping::default()).is_none());
+    }
+
+    #[test]
+    fn test_unrelated_events_are_ignored() {
+        let drop = event(doc! { "operationType": "drop" });
+        assert!(level_request_from_event(&drop, &SchemaMapping::default()).is_none());
+
+        let missing_user = event(doc! {
+            "operationType": "insert",
+            "fullDocument": { "_id": ObjectId::new(), "numLikes": 0 }
+        });
+        assert!(level_request_from_event(&missing_user, &SchemaMapping::default()).is_none());
+    }
+
+    fn command_error(code: i32, code_name: &str) -> mongodb::error::Error {
+        let error: CommandError = mongodb::bson::from_document(doc! {
+            "code": code,
+            "codeName": code_name,
+            "errmsg": "resume failed"
+        })
+        .unwrap();
+        ErrorKind::Command(error).into()
+    }
+
+    #[test]
+    fn test_history_lost_is_detected() {
+        assert!(is_history_lost(&command_error(
+            286,
+            "ChangeStreamHistoryLost"
+        )));
+        assert!(!is_history_lost(&command_error(
+            280,
+            "ChangeStreamFatalError"
+        )));
+        assert!(!is_history_lost(&mongodb::error::Error::custom("other")));
+    }
+
+    #[test]
+    fn test_pre_images_detection() {
+        assert_eq!(PreImages::detect("5.0.26", true), PreImages::Unsupported);
+        assert_eq!(PreImages::detect("6.0.0", false), PreImages::Disabled);
+        assert_eq!(PreImages::detect("7.0.12", true), PreImages::Enabled);
+        assert_eq!(PreImages::detect("", true), PreImages::Enabled);
+    }
+
+    #[test]
+    fn test_events_use_schema_mapping() {
+        let mut schema = SchemaMapping::default();
+        schema.recipe.user_id = "authorId".to_string();
+        schema.recipe.num_likes = "likeCount".to_string();
+
+        let user_id = ObjectId::new();
+        let likes_update = event(doc! {
+            "operationType": "update",
+            "updateDescription": { "updatedFields": { "likeCount": 4 }, "removedFields": [] },
+            "fullDocument": { "_id": ObjectId::new(), "authorId": user_id, "likeCount": 4 }
+        });
+        assert_eq!(
+            level_request_from_event(&likes_update, &schema)
+                .unwrap()
+                .user_id,
+            user_id.to_hex()
+        );
+
+        // The default field names no longer apply
+        let default_update = event(doc! {
+            "operationType": "update",
+            "updateDescription": { "updatedFields": { "numLikes": 4 }, "removedFields": [] },
+            "fullDocument": recipe(user_id)
+        });
+        assert!(level_request_from_event(&default_update, &schema).is_none());
+    }
+
+    /// Requires a replica set, e.g.
+    /// `docker run -d -p 27017:27017 mongo:7 --replSet rs0` followed by
+    /// `docker exec <container> mongosh --eval "rs.initiate()"`, then
+    /// `MONGODB_URI="mongodb://localhost:27017/?directConnection=true" cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore]
+    async fn test_watcher_against_replica_set() {
+        let uri = std::env::var("MONGODB_URI").expect("MONGODB_URI must point to a replica set");
+        let client = mongodb::Client::with_uri_str(uri).await.unwrap();
+        let db_name = format!("badge_forge_watch_{}", ObjectId::new().to_hex());
+
+        let (queue, _receiver) = InMemoryQueue::new(10);
+        let queue = Arc::new(queue);
+        RecipeWatcher::new(
+            client.clone(),
+            db_name.clone(),
+            queue.clone() as Arc<dyn BadgeUpdateQueue>,
+        )
+        .start()
+        .await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let user_id = ObjectId::new();
+        client
+            .database(&db_name)
+            .collection::<Document>("Recipe")
+            .insert_one(recipe(user_id))
+            .await
+            .unwrap();
+
+        let mut pending = Vec::new();
+        for _ in 0..50 {
+            pending = queue.get_pending_requests().await;
+            if !pending.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        client.database(&db_name).drop().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].user_id, user_id.to_hex());
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{
+        MockDatabase, MockNotifier, create_test_recipes, create_test_user,
+    };
+    use badge_forge::error::Error;
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::queue::{BadgeUpdateQueue, InMemoryQueue};
+    use badge_forge::service::badge_processor::BadgeForgeProcessor;
+    use badge_forge::service::connectivity::{
+        Connectivity, ConnectivityConfig, ConnectivityMonitor,
+    };
+    use chrono::Utc;
+    use std::sync::Arc;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    fn fast_config(startup_timeout: Duration) -> ConnectivityConfig {
+        ConnectivityConfig {
+            startup_timeout,
+            check_interval: Duration::from_millis(20),
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(20),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_startup_retries_until_reachable() {
+        let db = Arc::new(MockDatabase::new());
+        db.failing_pings.store(3, Ordering::SeqCst);
+        let connectivity = Connectivity::new(false);
+        let monitor = ConnectivityMonitor::new(
+            db.clone(),
+            connectivity.clone(),
+            fast_config(Duration::from_secs(5)),
+        );
+
+        monitor.wait_for_startup().await.unwrap();
+        assert_eq!(db.failing_pings.load(Ordering::SeqCst), 0);
+        // Readiness is left to the caller, which may still have setup to do
+        assert!(!connectivity.is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_startup_gives_up_after_timeout() {
+        let db = Arc::new(MockDatabase::new());
+        db.failing_pings.store(usize::MAX, Ordering::SeqCst);
+        let monitor = ConnectivityMonitor::new(
+            db,
+            Connectivity::new(false),
+            fast_config(Duration::from_millis(50)),
+        );
+
+        let result = monitor.wait_for_startup().await;
+        assert!(matches!(result, Err(Error::Transient(_))));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_tracks_outages() {
+        let db = Arc::new(MockDatabase::new());
+        let connectivity = Connectivity::new(false);
+        let monitor = ConnectivityMonitor::new(
+            db.clone(),
+            connectivity.clone(),
+            fast_config(Duration::ZERO),
+        );
+        tokio::spawn(monitor.run());
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(connectivity.is_ready());
+
+        db.failing_pings.store(usize::MAX, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(!connectivity.is_ready());
+
+        db.failing_pings.store(0, Ordering::SeqCst);
+        tokio::time::timeout(Duration::from_secs(1), connectivity.wait_until_ready())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_processor_pauses_while_not_ready() {
+        let db = Arc::new(MockDatabase::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 10, 10));
+
+        let connectivity = Connectivity::new(false);
+        let (queue, receiver) = InMemoryQueue::new(10);
+        let queue = Arc::new(queue);
+        BadgeForgeProcessor::new(db.clone(), Arc::new(MockNotifier::new()))
+            .with_connectivity(connectivity.clone())
+            .start(receiver, queue.clone())
+            .await;
+
+        queue
+            .enqueue(LevelRequest {
+                user_id: user_id.to_hex(),
+                request_id: String::new(),
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(db.get_user(&user_id).level, 0);
+        assert_eq!(queue.get_pending_requests().await.len(), 1);
+
+        connectivity.set_ready(true);
+        for _ in 0..50 {
+            if queue.get_pending_requests().await.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(db.get_user(&user_id).level, 110);
+    }
+}
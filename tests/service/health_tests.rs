@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::MockNotifier;
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::queue::{BadgeUpdateQueue, InMemoryQueue};
+    use badge_forge::service::connectivity::Connectivity;
+    use badge_forge::service::health::{ComponentStatus, check_readiness};
+    use badge_forge::service::heartbeat::Heartbeat;
+    use chrono::{Duration, Utc};
+
+    fn beating() -> Heartbeat {
+        let heartbeat = Heartbeat::new();
+        heartbeat.beat();
+        heartbeat
+    }
+
+    #[tokio::test]
+    async fn test_ready_when_all_components_ok() {
+        let (queue, _receiver) = InMemoryQueue::new(10);
+        let report = check_readiness(
+            &Connectivity::new(true),
+            &beating(),
+            &queue,
+            &MockNotifier::new(),
+            Utc::now(),
+        )
+        .await;
+
+        assert!(report.ready);
+        assert_eq!(report.components.len(), 4);
+        assert!(
+            report
+                .components
+                .values()
+                .all(|c| c.status == ComponentStatus::Ok)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failing_components_are_reported() {
+        let (queue, _receiver) = InMemoryQueue::new(10);
+        for _ in 0..9 {
+            queue
+                .enqueue(LevelRequest {
+                    user_id: "669b7be8f163ac944bc8a16e".to_string(),
+                    request_id: String::new(),
+                    created_at: Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+
+        // A processor that stopped beating two minutes ago
+        let report = check_readiness(
+            &Connectivity::new(false),
+            &beating(),
+            &queue,
+            &MockNotifier::new(),
+            Utc::now() + Duration::minutes(2),
+        )
+        .await;
+
+        assert!(!report.ready);
+        assert_eq!(report.components["database"].status, ComponentStatus::Fail);
+        assert_eq!(report.components["processor"].status, ComponentStatus::Fail);
+        assert_eq!(report.components["queue"].status, ComponentStatus::Fail);
+        assert_eq!(
+            report.components["queue"].message.as_deref(),
+            Some("9 of 10 slots in use")
+        );
+        assert_eq!(report.components["notifier"].status, ComponentStatus::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_notifier_outage_only_warns() {
+        let (queue, _receiver) = InMemoryQueue::new(10);
+        let notifier = MockNotifier::new();
+        *notifier.check_error.lock().unwrap() = Some("connection refused".to_string());
+
+        let report = check_readiness(
+            &Connectivity::new(true),
+            &beating(),
+            &queue,
+            &notifier,
+            Utc::now(),
+        )
+        .await;
+
+        assert!(report.ready);
+        assert_eq!(report.components["notifier"].status, ComponentStatus::Warn);
+
+        let report = check_readiness(
+            &Connectivity::new(true),
+            &Heartbeat::new(),
+            &queue,
+            &notifier,
+            Utc::now(),
+        )
+        .await;
+        assert!(!report.ready);
+        assert_eq!(
+            report.components["processor"].message.as_deref(),
+            Some("Processor has not started")
+        );
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use badge_forge::service::indexes::{
+        IndexMode, IndexSpec, IndexStatus, index_status, required_indexes,
+    };
+    use badge_forge::service::schema::SchemaMapping;
+    use mongodb::IndexModel;
+    use mongodb::bson::doc;
+    use mongodb::options::IndexOptions;
+
+    fn existing_index(keys: mongodb::bson::Document, name: &str, unique: bool) -> IndexModel {
+        IndexModel::builder()
+            .keys(keys)
+            .options(
+                IndexOptions::builder()
+                    .name(name.to_string())
+                    .unique(unique.then_some(true))
+                    .build(),
+            )
+            .build()
+    }
+
+    fn recipe_index() -> IndexSpec {
+        required_indexes(&SchemaMapping::default())
+            .into_iter()
+            .find(|spec| spec.collection == "Recipe")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_required_indexes_cover_recipe_owner_lookup() {
+        let spec = recipe_index();
+        assert_eq!(spec.keys, doc! { "userId": 1 });
+        assert_eq!(spec.qualified_name(), "Recipe.badge_forge_user");
+
+        let pending = required_indexes(&SchemaMapping::default())
+            .into_iter()
+            .find(|spec| spec.name == "badge_forge_pending_user")
+            .unwrap();
+        assert!(pending.unique);
+        assert_eq!(pending.partial_filter, Some(doc! { "status": "pending" }));
+    }
+
+    #[test]
+    fn test_index_status_missing_and_present() {
+        let spec = recipe_index();
+        let id_index = existing_index(doc! { "_id": 1 }, "_id_", false);
+        assert_eq!(
+            index_status(&spec, std::slice::from_ref(&id_index)),
+            IndexStatus::Missing
+        );
+
+        // Servers may report numeric directions as doubles
+        let created = existing_index(doc! { "userId": 1.0 }, "badge_forge_user", false);
+        assert_eq!(
+            index_status(&spec, &[id_index, created]),
+            IndexStatus::Present
+        );
+
+        let model = spec.to_model();
+        assert_eq!(index_status(&spec, &[model]), IndexStatus::Present);
+
+        // Created by hand under the default name
+        let renamed = existing_index(doc! { "userId": 1 }, "userId_1", false);
+        assert_eq!(index_status(&spec, &[renamed]), IndexStatus::Present);
+    }
+
+    #[test]
+    fn test_index_status_reports_drift() {
+        let spec = recipe_index();
+
+        // Same keys under another name, but unique
+        let renamed_unique = existing_index(doc! { "userId": 1 }, "userId_1", true);
+        assert!(matches!(
+            index_status(&spec, &[renamed_unique]),
+            IndexStatus::Drifted(reason) if reason == "unique is true instead of false"
+        ));
+
+        let changed_keys = existing_index(
+            doc! { "userId": 1, "createdAt": -1 },
+            "badge_forge_user",
+            false,
+        );
+        assert!(matches!(
+            index_status(&spec, &[changed_keys]),
+            IndexStatus::Drifted(reason) if reason.contains("keys")
+        ));
+
+        let unique = existing_index(doc! { "userId": 1 }, "badge_forge_user", true);
+        assert!(matches!(
+            index_status(&spec, &[unique]),
+            IndexStatus::Drifted(reason) if reason.contains("unique")
+        ));
+    }
+
+    #[test]
+    fn test_index_mode_parse() {
+        assert_eq!(IndexMode::default(), IndexMode::Create);
+        assert_eq!(IndexMode::parse("check"), Some(IndexMode::Check));
+        assert_eq!(IndexMode::parse(" OFF "), Some(IndexMode::Off));
+        assert_eq!(IndexMode::parse("create"), Some(IndexMode::Create));
+        assert_eq!(IndexMode::parse("false"), None);
+    }
+}
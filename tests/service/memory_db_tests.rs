@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{MockNotifier, create_test_recipes, create_test_user};
+    use badge_forge::model::award::TopRecipeAward;
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::service::badge_processor::BadgeForgeProcessor;
+    use badge_forge::service::db::{Database, MemoryDatabase, MemorySnapshot};
+    use chrono::{TimeZone, Utc};
+    use mongodb::bson::oid::ObjectId;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/memory_db.json");
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("badge_forge_{}.json", ObjectId::new().to_hex()))
+    }
+
+    #[tokio::test]
+    async fn test_seed_from_fixture() {
+        let snapshot = MemorySnapshot::load(std::path::Path::new(FIXTURE)).unwrap();
+        assert_eq!(snapshot.users.len(), 2);
+        assert_eq!(snapshot.recipes.len(), 4);
+
+        let db = MemoryDatabase::from_snapshot(snapshot);
+        let ana = ObjectId::parse_str("669b7be8f163ac944bc8a16e").unwrap();
+        let user = db.find_user(&ana).await.unwrap().unwrap();
+        assert_eq!(user.email.as_deref(), Some("ana@example.com"));
+        assert_eq!(
+            user.created_at,
+            Some(Utc.with_ymd_and_hms(2024, 9, 1, 10, 0, 0).unwrap())
+        );
+
+        // Both plain and `$date` dates are accepted
+        let stats = db.get_user_stats(&ana).await.unwrap();
+        assert_eq!(stats.num_recipes, 3);
+        assert_eq!(stats.total_likes, 46);
+        assert_eq!(stats.active_weeks, 3);
+    }
+
+    #[test]
+    fn test_invalid_fixture_is_rejected() {
+        assert!(MemorySnapshot::from_json("{ \"users\": [{ \"name\": \"no id\" }] }").is_err());
+        assert!(MemorySnapshot::from_json("not json").is_err());
+        assert!(MemorySnapshot::from_json("{}").unwrap().users.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_processor_runs_against_memory_database() {
+        let db = Arc::new(MemoryDatabase::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user);
+        for recipe in create_test_recipes(user_id, 10, 10) {
+            db.insert_recipe(recipe);
+        }
+
+        let notifier = Arc::new(MockNotifier::new());
+        let processor = BadgeForgeProcessor::new(db.clone(), notifier.clone());
+        processor
+            .process_request(LevelRequest {
+                user_id: user_id.to_hex(),
+                request_id: "memory".to_string(),
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let updated = db.find_user(&user_id).await.unwrap().unwrap();
+        assert_eq!(updated.level, 110);
+        assert!(updated.badges.contains(&"level_100".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_persistence_survives_restart() {
+        let path = temp_path();
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+
+        {
+            let db = MemoryDatabase::from_snapshot(MemorySnapshot {
+                users: vec![user],
+                recipes: create_test_recipes(user_id, 2, 5),
+                reviews: vec![],
+            })
+            .with_persistence(&path)
+            .unwrap();
+
+            let award = TopRecipeAward {
+                badge: "recipe_of_the_week".to_string(),
+                period: "2025-W23".to_string(),
+                recipe_id: None,
+                awarded_at: Utc::now(),
+            };
+            db.record_top_recipe_award(&user_id, &award).await.unwrap();
+            db.set_verification(&user_id, true, true, "Known chef", "admin")
+                .await
+                .unwrap();
+        }
+
+        // The persisted file wins over seed data on the next start
+        let db = MemoryDatabase::new().with_persistence(&path).unwrap();
+        let user = db.find_user(&user_id).await.unwrap().unwrap();
+        assert!(user.badges.contains(&"recipe_of_the_week".to_string()));
+        assert_eq!(user.top_recipe_wins("recipe_of_the_week"), 1);
+        assert_eq!(user.verified, Some(true));
+        assert_eq!(user.verification_locked, Some(true));
+        assert_eq!(db.get_user_recipes(&user_id).await.unwrap().len(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_persist_leaves_state_unchanged() {
+        let dir = temp_path();
+        std::fs::create_dir(&dir).unwrap();
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        let db = MemoryDatabase::from_snapshot(MemorySnapshot {
+            users: vec![user],
+            ..MemorySnapshot::default()
+        })
+        .with_persistence(dir.join("state.json"))
+        .unwrap();
+
+        // Saving fails once the directory is gone
+        std::fs::remove_dir_all(&dir).unwrap();
+        let result = db.add_badge_to_user(&user_id, "level_100").await;
+        assert!(result.is_err());
+        let user = db.find_user(&user_id).await.unwrap().unwrap();
+        assert!(user.badges.is_empty());
+
+        // Writes take effect again once saving works
+        std::fs::create_dir(&dir).unwrap();
+        let result = db.add_badge_to_user(&user_id, "level_100").await;
+        assert_eq!(result.unwrap(), Some(true));
+        let saved = MemorySnapshot::load(&dir.join("state.json")).unwrap();
+        assert_eq!(saved.users[0].badges, vec!["level_100".to_string()]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
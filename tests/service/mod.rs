@@ -1 +1,15 @@
+pub mod abuse_tests;
+pub mod badge_processor_tests;
+pub mod cache_db_tests;
+pub mod change_stream_tests;
+pub mod connectivity_tests;
+pub mod health_tests;
+pub mod index_tests;
+pub mod memory_db_tests;
 pub mod notifier_tests;
+pub mod schema_tests;
+pub mod shutdown_tests;
+pub mod sqlite_db_tests;
+pub mod supervision_tests;
+pub mod tracking_tests;
+pub mod verification_tests;
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use axum::{Router, routing::post};
+    use axum::{Router, http::StatusCode, routing::post};
     use badge_forge::service::notifier::{HttpNotifier, Notifier};
     use serde_json::json;
     use std::sync::Arc;
@@ -45,6 +45,39 @@ mod tests {
         assert_eq!(req_count.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn test_check_accepts_post_only_endpoint() {
+        let (url, req_count) = run_mock_server().await;
+
+        let notifier = HttpNotifier::new(url, "test_api_key".to_string());
+        assert_eq!(notifier.check().await, Ok(()));
+        // The health check sends no notification
+        assert_eq!(req_count.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_server_errors() {
+        let app = Router::new().route(
+            "/notifications",
+            post(|| async { "OK" }).head(|| async { StatusCode::SERVICE_UNAVAILABLE }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let notifier = HttpNotifier::new(url, "test_api_key".to_string());
+        assert_eq!(
+            notifier.check().await,
+            Err("Notifier unhealthy: 503 Service Unavailable".to_string())
+        );
+
+        // Connection failures are reported too
+        let notifier = HttpNotifier::new("http://127.0.0.1:1".to_string(), String::new());
+        assert!(notifier.check().await.is_err());
+    }
+
     #[tokio::test]
     async fn test_send_notification_empty_url() {
         let notifier = HttpNotifier::new("".to_string(), "test_api_key".to_string());
@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod tests {
+    use badge_forge::model::award::TopRecipeAward;
+    use badge_forge::service::db::{BadgeUpdate, Database, MongoDatabase};
+    use badge_forge::service::schema::SchemaMapping;
+    use chrono::{TimeZone, Utc};
+    use mongodb::bson::{Document, doc, oid::ObjectId};
+
+    fn forked_schema() -> SchemaMapping {
+        let mut schema = SchemaMapping {
+            user_collection: "members".to_string(),
+            recipe_collection: "dishes".to_string(),
+            ..Default::default()
+        };
+        schema.recipe.user_id = "authorId".to_string();
+        schema.recipe.num_likes = "likeCount".to_string();
+        schema.recipe.created_at = "publishedAt".to_string();
+        schema.user.email = "mail".to_string();
+        schema.user.level = "rank".to_string();
+        schema.user.badges = "achievements".to_string();
+        schema.user.verified = "trusted".to_string();
+        schema.user.verification_locked = "trustLocked".to_string();
+        schema
+    }
+
+    #[test]
+    fn test_default_schema_is_valid() {
+        assert!(SchemaMapping::default().validate().is_ok());
+        assert!(forked_schema().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_mappings() {
+        let mut schema = SchemaMapping::default();
+        schema.recipe.num_likes = "$likes".to_string();
+        assert!(schema.validate().is_err());
+
+        let mut schema = SchemaMapping::default();
+        schema.recipe.num_likes = "stats.likes".to_string();
+        assert!(schema.validate().is_err());
+
+        let mut schema = SchemaMapping::default();
+        schema.recipe.num_likes = "userId".to_string();
+        assert!(schema.validate().is_err());
+
+        // A read field mapped onto a field badge_forge writes
+        let mut schema = SchemaMapping::default();
+        schema.user.name = "level".to_string();
+        assert!(schema.validate().is_err());
+
+        let schema = SchemaMapping {
+            recipe_collection: "User".to_string(),
+            ..Default::default()
+        };
+        assert!(schema.validate().is_err());
+    }
+
+    #[test]
+    fn test_recipe_from_mapped_document() {
+        let user_id = ObjectId::new();
+        let published = Utc.with_ymd_and_hms(2025, 3, 1, 10, 0, 0).unwrap();
+        let document = doc! {
+            "_id": ObjectId::new(),
+            "authorId": user_id,
+            "likeCount": 12,
+            "publishedAt": mongodb::bson::DateTime::from_millis(published.timestamp_millis()),
+            "title": "Paella"
+        };
+
+        let recipe = forked_schema().recipe_from_document(document).unwrap();
+        assert_eq!(recipe.user_id, user_id);
+        assert_eq!(recipe.num_likes, 12);
+        assert_eq!(recipe.created_at, published);
+    }
+
+    #[test]
+    fn test_user_from_mapped_document() {
+        let document = doc! {
+            "_id": ObjectId::new(),
+            "name": "Ana",
+            "mail": "ana@example.com",
+            // Unmapped under the fork's schema, so it must not be read as the email
+            "email": "stale@example.com",
+            "rank": 7,
+            "level": 3,
+            "achievements": ["level_5"],
+            "trusted": true,
+            "trustLocked": true
+        };
+
+        let user = forked_schema().user_from_document(document).unwrap();
+        assert_eq!(user.email.as_deref(), Some("ana@example.com"));
+        assert_eq!(user.name.as_deref(), Some("Ana"));
+        assert_eq!(user.level, 7);
+        assert_eq!(user.badges, vec!["level_5".to_string()]);
+        assert_eq!(user.verified, Some(true));
+        assert_eq!(user.verification_locked, Some(true));
+    }
+
+    /// Requires a MongoDB server, e.g. `docker run -d -p 27017:27017 mongo:7`, then
+    /// `MONGODB_URI="mongodb://localhost:27017" cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore]
+    async fn test_writes_use_mapped_fields() {
+        let uri = std::env::var("MONGODB_URI").expect("MONGODB_URI must point to a server");
+        let client = mongodb::Client::with_uri_str(uri).await.unwrap();
+        let db_name = format!("badge_forge_schema_{}", ObjectId::new().to_hex());
+        let db = MongoDatabase::new(client.clone(), db_name.clone()).with_schema(forked_schema());
+        let members = client.database(&db_name).collection::<Document>("members");
+
+        let user_id = ObjectId::new();
+        members
+            .insert_one(doc! { "_id": user_id, "name": "Ana", "rank": 0 })
+            .await
+            .unwrap();
+        let update = BadgeUpdate {
+            expected_level: 0,
+            level: 110,
+            add_badges: vec!["level_100".to_string()],
+            verified: Some(true),
+        };
+        assert!(db.apply_badge_update(&user_id, &update).await.unwrap());
+        // The level guard reads the mapped field, so a stale expected level is rejected
+        assert!(!db.apply_badge_update(&user_id, &update).await.unwrap());
+        db.set_verification(&user_id, false, true, "spam", "admin")
+            .await
+            .unwrap();
+        let award = TopRecipeAward {
+            badge: "top_recipe_of_the_week".to_string(),
+            period: "2025-W10".to_string(),
+            recipe_id: None,
+            awarded_at: Utc::now(),
+        };
+        let outcome = db.record_top_recipe_award(&user_id, &award).await.unwrap();
+
+        let stored = members.find_one(doc! { "_id": user_id }).await.unwrap();
+        client.database(&db_name).drop().await.unwrap();
+        let stored = stored.unwrap();
+        assert_eq!(outcome.unwrap().win_count, 1);
+        assert_eq!(stored.get_i32("rank").unwrap(), 110);
+        assert_eq!(stored.get_array("achievements").unwrap().len(), 2);
+        assert!(!stored.get_bool("trusted").unwrap());
+        assert!(stored.get_bool("trustLocked").unwrap());
+        for field in ["level", "badges", "verified", "verificationLocked"] {
+            assert!(!stored.contains_key(field), "{} was written", field);
+        }
+    }
+
+    #[test]
+    fn test_check_samples_reports_field_coverage() {
+        let schema = forked_schema();
+        let recipes = vec![
+            doc! { "_id": ObjectId::new(), "authorId": ObjectId::new(), "publishedAt": 1 },
+            doc! { "_id": ObjectId::new(), "authorId": ObjectId::new(), "likeCount": 3, "publishedAt": 2 },
+        ];
+        let users = vec![doc! { "_id": ObjectId::new(), "email": "a@example.com" }];
+
+        let coverage = schema.check_samples(&recipes, &users);
+        let field = |name: &str| coverage.iter().find(|f| f.field == name).unwrap();
+
+        assert!(!field("authorId").is_missing());
+        assert!(field("likeCount").is_partial());
+        assert!(field("mail").is_missing());
+        assert!(!field("mail").required);
+        assert!(field("authorId").required);
+        assert_eq!(field("authorId").collection, "dishes");
+
+        // Empty collections cannot be checked
+        let coverage = schema.check_samples(&[], &[]);
+        assert!(coverage.iter().all(|f| !f.is_missing() && !f.is_partial()));
+    }
+}
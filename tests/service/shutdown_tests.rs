@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{
+        MockDatabase, MockNotifier, build_test_state, create_test_recipes, create_test_user,
+    };
+    use axum::http::StatusCode;
+    use axum::test_helpers::TestClient;
+    use badge_forge::api::{route::create_router, state::AppState};
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::queue::{BadgeUpdateQueue, InMemoryQueue};
+    use badge_forge::service::badge_processor::BadgeForgeProcessor;
+    use badge_forge::service::heartbeat::Heartbeat;
+    use badge_forge::service::shutdown::{
+        Shutdown, drain_queue, save_pending_requests, take_pending_requests,
+    };
+    use chrono::Utc;
+    use mongodb::bson::oid::ObjectId;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("badge_forge_{}.json", ObjectId::new().to_hex()))
+    }
+
+    fn request(user_id: &ObjectId) -> LevelRequest {
+        LevelRequest {
+            user_id: user_id.to_hex(),
+            request_id: ObjectId::new().to_hex(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_updates_rejected_while_shutting_down() {
+        let shutdown = Shutdown::new();
+        let heartbeat = Heartbeat::new();
+        heartbeat.beat();
+        let (queue, _receiver) = InMemoryQueue::new(10);
+        let state = Arc::new(AppState {
+            badge_queue: Arc::new(queue),
+            shutdown: shutdown.clone(),
+            processor_heartbeat: heartbeat,
+            ..build_test_state(Arc::new(MockDatabase::new()), Arc::new(MockNotifier::new()))
+        });
+        let client = TestClient::new(create_router(state.clone()));
+        dotenv::dotenv().ok();
+        let api_key = std::env::var("API_KEY").unwrap_or_else(|_| "default_key".to_string());
+        let body = json!({ "user_id": ObjectId::new().to_hex() });
+
+        let response = client
+            .post("/update")
+            .header("X-API-Key", &api_key)
+            .json(&body)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(client.get("/health/ready").await.status(), StatusCode::OK);
+
+        shutdown.trigger();
+        assert!(shutdown.is_triggered());
+        let response = client
+            .post("/update")
+            .header("X-API-Key", &api_key)
+            .json(&body)
+            .await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(state.badge_queue.get_pending_requests().await.len(), 1);
+
+        let response = client.get("/health/ready").await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body: serde_json::Value = response.json().await;
+        assert_eq!(body["status"], "shutting_down");
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_for_processor() {
+        let db = Arc::new(MockDatabase::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 10, 10));
+
+        let (queue, receiver) = InMemoryQueue::new(10);
+        let queue = Arc::new(queue);
+        queue.enqueue(request(&user_id)).await.unwrap();
+        queue.enqueue(request(&user_id)).await.unwrap();
+        BadgeForgeProcessor::new(db.clone(), Arc::new(MockNotifier::new()))
+            .start(receiver, queue.clone())
+            .await;
+
+        let pending = drain_queue(queue.as_ref(), Duration::from_secs(5)).await;
+        assert!(pending.is_empty());
+        assert_eq!(db.get_user(&user_id).level, 110);
+    }
+
+    #[tokio::test]
+    async fn test_drain_returns_pending_at_deadline() {
+        // Nothing takes requests from the queue
+        let (queue, _receiver) = InMemoryQueue::new(10);
+        let stuck = request(&ObjectId::new());
+        queue.enqueue(stuck.clone()).await.unwrap();
+
+        let pending = drain_queue(&queue, Duration::from_millis(50)).await;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].request_id, stuck.request_id);
+    }
+
+    #[tokio::test]
+    async fn test_pending_requests_round_trip() {
+        let path = temp_path();
+        assert!(take_pending_requests(&path).unwrap().is_empty());
+
+        let requests = vec![request(&ObjectId::new()), request(&ObjectId::new())];
+        save_pending_requests(&path, &requests).unwrap();
+
+        let restored = take_pending_requests(&path).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].request_id, requests[0].request_id);
+        assert_eq!(restored[1].user_id, requests[1].user_id);
+        assert_eq!(restored[1].created_at, requests[1].created_at);
+
+        // Restored only once
+        assert!(!path.exists());
+        assert!(take_pending_requests(&path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{create_test_recipes, create_test_user};
+    use badge_forge::model::award::TopRecipeAward;
+    use badge_forge::model::stats::UserStats;
+    use badge_forge::service::db::{Database, MemorySnapshot, SqliteDatabase};
+    use chrono::Utc;
+    use mongodb::bson::oid::ObjectId;
+    use std::path::PathBuf;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/memory_db.json");
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!("badge_forge_{}.sqlite3", ObjectId::new().to_hex()))
+    }
+
+    #[tokio::test]
+    async fn test_stats_match_recipe_based_computation() {
+        let snapshot = MemorySnapshot::load(std::path::Path::new(FIXTURE)).unwrap();
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        db.import(&snapshot).unwrap();
+
+        for user in &snapshot.users {
+            let recipes: Vec<_> = snapshot
+                .recipes
+                .iter()
+                .filter(|recipe| recipe.user_id == user._id)
+                .cloned()
+                .collect();
+            assert_eq!(
+                db.get_user_stats(&user._id).await.unwrap(),
+                UserStats::from_recipes(&recipes)
+            );
+            assert_eq!(
+                db.get_user_recipes(&user._id).await.unwrap().len(),
+                recipes.len()
+            );
+        }
+
+        let ana = ObjectId::parse_str("669b7be8f163ac944bc8a16e").unwrap();
+        let user = db.find_user(&ana).await.unwrap().unwrap();
+        let expected = &snapshot.users[0];
+        assert_eq!(user.email, expected.email);
+        assert_eq!(user.created_at, expected.created_at);
+        assert_eq!(user.badges, expected.badges);
+    }
+
+    #[tokio::test]
+    async fn test_data_and_migrations_survive_reopen() {
+        let path = temp_path();
+        let user = create_test_user(vec!["level_100".to_string()]);
+        let user_id = user._id;
+
+        {
+            let db = SqliteDatabase::open(&path).unwrap();
+            assert!(db.is_empty().unwrap());
+            db.import(&MemorySnapshot {
+                users: vec![user],
+                recipes: create_test_recipes(user_id, 3, 5),
+                reviews: vec![],
+            })
+            .unwrap();
+
+            let award = TopRecipeAward {
+                badge: "recipe_of_the_week".to_string(),
+                period: "2025-W23".to_string(),
+                recipe_id: Some(ObjectId::new()),
+                awarded_at: Utc::now(),
+            };
+            let outcome = db.record_top_recipe_award(&user_id, &award).await.unwrap();
+            assert!(outcome.unwrap().newly_awarded);
+            let outcome = db.record_top_recipe_award(&user_id, &award).await.unwrap();
+            assert_eq!(outcome.unwrap().win_count, 1);
+        }
+
+        // Reopening applies no migration twice and keeps the data
+        let db = SqliteDatabase::open(&path).unwrap();
+        let version = db.schema_version().unwrap();
+        assert!(version >= 1);
+        assert!(!db.is_empty().unwrap());
+
+        let user = db.find_user(&user_id).await.unwrap().unwrap();
+        assert_eq!(user.badges, vec!["level_100", "recipe_of_the_week"]);
+        assert_eq!(user.top_recipe_wins("recipe_of_the_week"), 1);
+        assert_eq!(db.get_user_stats(&user_id).await.unwrap().total_likes, 15);
+
+        drop(db);
+        assert_eq!(
+            SqliteDatabase::open(&path)
+                .unwrap()
+                .schema_version()
+                .unwrap(),
+            version
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_user_writes() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        let user_id = ObjectId::new();
+
+        assert_eq!(
+            db.add_badge_to_user(&user_id, "level_100").await.unwrap(),
+            None
+        );
+        assert!(
+            !db.set_verification(&user_id, true, false, "x", "admin")
+                .await
+                .unwrap()
+        );
+        let award = TopRecipeAward {
+            badge: "recipe_of_the_week".to_string(),
+            period: "2025-W23".to_string(),
+            recipe_id: None,
+            awarded_at: Utc::now(),
+        };
+        assert_eq!(
+            db.record_top_recipe_award(&user_id, &award).await.unwrap(),
+            None
+        );
+    }
+}
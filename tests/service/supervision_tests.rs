@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{
+        MockDatabase, MockNotifier, build_test_state, create_test_recipes, create_test_user,
+    };
+    use axum::test_helpers::TestClient;
+    use badge_forge::api::{route::create_metrics_router, state::AppState};
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::queue::{BadgeUpdateQueue, InMemoryQueue};
+    use badge_forge::service::badge_processor::BadgeForgeProcessor;
+    use badge_forge::service::heartbeat::Heartbeat;
+    use badge_forge::service::metrics::{ProcessorMetrics, ProcessorStats};
+    use chrono::Utc;
+    use mongodb::bson::oid::ObjectId;
+    use std::sync::Arc;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    fn request(user_id: &ObjectId) -> LevelRequest {
+        LevelRequest {
+            user_id: user_id.to_hex(),
+            request_id: String::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    async fn wait_for_empty_queue(queue: &InMemoryQueue) {
+        for _ in 0..100 {
+            if queue.get_pending_requests().await.is_empty() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("Queue was not drained");
+    }
+
+    #[tokio::test]
+    async fn test_panicking_request_does_not_stop_processor() {
+        let db = Arc::new(MockDatabase::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 10, 10));
+        db.panic_on_find_user.store(true, Ordering::SeqCst);
+
+        let metrics = Arc::new(ProcessorMetrics::default());
+        let heartbeat = Heartbeat::new();
+        let (queue, receiver) = InMemoryQueue::new(10);
+        let queue = Arc::new(queue);
+        BadgeForgeProcessor::new(db.clone(), Arc::new(MockNotifier::new()))
+            .with_metrics(metrics.clone())
+            .with_heartbeat(heartbeat.clone())
+            .start(receiver, queue.clone())
+            .await;
+
+        queue.enqueue(request(&user_id)).await.unwrap();
+        queue.enqueue(request(&user_id)).await.unwrap();
+        wait_for_empty_queue(&queue).await;
+
+        assert_eq!(db.get_user(&user_id).level, 110);
+        assert_eq!(
+            metrics.snapshot(),
+            ProcessorStats {
+                processed: 1,
+                failed: 1,
+                panics: 1,
+                restarts: 0,
+            }
+        );
+        assert!(heartbeat.last_beat().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_multiple_workers_drain_queue() {
+        let db = Arc::new(MockDatabase::new());
+        let (queue, receiver) = InMemoryQueue::new(10);
+        let queue = Arc::new(queue);
+        let metrics = Arc::new(ProcessorMetrics::default());
+        BadgeForgeProcessor::new(db.clone(), Arc::new(MockNotifier::new()))
+            .with_metrics(metrics.clone())
+            .with_worker_count(3)
+            .start(receiver, queue.clone())
+            .await;
+
+        let mut user_ids = Vec::new();
+        for _ in 0..6 {
+            let user = create_test_user(vec![]);
+            let user_id = user._id;
+            db.insert_user(user, create_test_recipes(user_id, 10, 10));
+            queue.enqueue(request(&user_id)).await.unwrap();
+            user_ids.push(user_id);
+        }
+        wait_for_empty_queue(&queue).await;
+
+        for user_id in &user_ids {
+            assert_eq!(db.get_user(user_id).level, 110);
+        }
+        assert_eq!(metrics.snapshot().processed, 6);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let metrics = Arc::new(ProcessorMetrics::default());
+        let heartbeat = Heartbeat::new();
+        heartbeat.beat();
+        let state = AppState {
+            processor_metrics: metrics,
+            processor_heartbeat: heartbeat,
+            ..build_test_state(Arc::new(MockDatabase::new()), Arc::new(MockNotifier::new()))
+        };
+        let client = TestClient::new(create_metrics_router(Arc::new(state)));
+
+        let response = client.get("/metrics").await;
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let body = response.text().await;
+        assert!(
+            body.contains("# TYPE badge_forge_processor_up gauge\nbadge_forge_processor_up 1\n")
+        );
+        assert!(body.contains("badge_forge_processor_restarts_total 0\n"));
+        assert!(body.contains("badge_forge_queue_capacity 100\n"));
+        assert!(body.contains("badge_forge_database_up 1\n"));
+        assert!(!body.contains("badge_forge_cache_hits_total"));
+    }
+}
@@ -0,0 +1,221 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::{
+        MockDatabase, MockNotifier, build_test_state, create_test_recipes, create_test_user,
+        test_api_key,
+    };
+    use axum::http::StatusCode;
+    use axum::test_helpers::TestClient;
+    use badge_forge::api::{route::create_router, state::AppState};
+    use badge_forge::model::level::LevelRequest;
+    use badge_forge::queue::InMemoryQueue;
+    use badge_forge::service::badge_processor::BadgeForgeProcessor;
+    use badge_forge::service::tracking::{
+        RequestOutcome, RequestState, RequestTracker, TrackingConfig,
+    };
+    use chrono::Utc;
+    use mongodb::bson::oid::ObjectId;
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn request(user_id: &str) -> LevelRequest {
+        LevelRequest {
+            user_id: user_id.to_string(),
+            request_id: ObjectId::new().to_hex(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn outcome(level: i32) -> RequestOutcome {
+        RequestOutcome {
+            previous_level: 0,
+            level,
+            badges_added: vec![],
+            verified: false,
+            newly_verified: false,
+            held_for_review: false,
+        }
+    }
+
+    /// A client whose queue and processor report to the tracker behind `/requests`.
+    async fn client(db: Arc<MockDatabase>) -> TestClient {
+        let tracker = RequestTracker::new(TrackingConfig::default());
+        let (queue, receiver) = InMemoryQueue::new(10);
+        let queue = Arc::new(queue.with_tracker(tracker.clone()));
+        BadgeForgeProcessor::new(db.clone(), Arc::new(MockNotifier::new()))
+            .with_tracker(tracker.clone())
+            .start(receiver, queue.clone())
+            .await;
+        let state = AppState {
+            badge_queue: queue,
+            requests: tracker,
+            ..build_test_state(db, Arc::new(MockNotifier::new()))
+        };
+        TestClient::new(create_router(Arc::new(state)))
+    }
+
+    /// Enqueues an update for `user_id` and polls its status until it is finished.
+    async fn update_and_wait(client: &TestClient, user_id: &str) -> Value {
+        let response = client
+            .post("/update")
+            .header("X-API-Key", test_api_key())
+            .json(&json!({ "user_id": user_id }))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: Value = response.json().await;
+        let request_id = body["request_id"].as_str().unwrap().to_string();
+
+        for _ in 0..100 {
+            let response = client
+                .get(&format!("/requests/{}", request_id))
+                .header("X-API-Key", test_api_key())
+                .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: Value = response.json().await;
+            if body["request"]["state"] == "completed" || body["request"]["state"] == "failed" {
+                assert_eq!(body["request"]["request_id"], request_id.as_str());
+                return body["request"].clone();
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("Request {} did not finish", request_id);
+    }
+
+    fn states(request: &Value) -> Vec<&str> {
+        request["transitions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|transition| transition["state"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_completed_request_reports_outcome() {
+        let db = Arc::new(MockDatabase::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 10, 10));
+        let client = client(db.clone()).await;
+
+        let request = update_and_wait(&client, &user_id.to_hex()).await;
+        assert_eq!(states(&request), ["queued", "processing", "completed"]);
+        assert_eq!(request["user_id"], user_id.to_hex());
+        assert_eq!(request["outcome"]["previous_level"], 0);
+        assert_eq!(request["outcome"]["level"], 110);
+        let badges_added: Vec<String> =
+            serde_json::from_value(request["outcome"]["badges_added"].clone()).unwrap();
+        assert_eq!(badges_added, db.get_user(&user_id).badges);
+        assert!(request.get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_failed_request_reports_error() {
+        let client = client(Arc::new(MockDatabase::new())).await;
+        let user_id = ObjectId::new().to_hex();
+
+        let request = update_and_wait(&client, &user_id).await;
+        assert_eq!(states(&request), ["queued", "processing", "failed"]);
+        assert_eq!(request["error"], format!("User not found: {}", user_id));
+        assert!(request.get("outcome").is_none());
+
+        let response = client
+            .get("/requests/unknown")
+            .header("X-API-Key", test_api_key())
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_client_request_ids_are_ignored() {
+        let db = Arc::new(MockDatabase::new());
+        let user = create_test_user(vec![]);
+        let user_id = user._id;
+        db.insert_user(user, create_test_recipes(user_id, 10, 10));
+        let client = client(db).await;
+
+        let first = update_and_wait(&client, &user_id.to_hex()).await;
+        let response = client
+            .post("/update")
+            .header("X-API-Key", test_api_key())
+            .json(&json!({
+                "user_id": user_id.to_hex(),
+                "request_id": first["request_id"]
+            }))
+            .await;
+        let body: Value = response.json().await;
+        assert_ne!(body["request_id"], first["request_id"]);
+
+        // The earlier request keeps its own outcome
+        let response = client
+            .get(&format!(
+                "/requests/{}",
+                first["request_id"].as_str().unwrap()
+            ))
+            .header("X-API-Key", test_api_key())
+            .await;
+        let tracked: Value = response.json().await;
+        assert_eq!(tracked["request"]["outcome"], first["outcome"]);
+        assert_eq!(tracked["request"]["created_at"], first["created_at"]);
+    }
+
+    #[tokio::test]
+    async fn test_tracker_is_bounded() {
+        let tracker = RequestTracker::new(TrackingConfig {
+            ttl: Duration::from_secs(60),
+            max_requests: 2,
+        });
+        let requests: Vec<LevelRequest> = (0..3).map(|_| request("user")).collect();
+        for request in &requests {
+            tracker.queued(request);
+        }
+        assert!(tracker.get(&requests[0].request_id).is_none());
+        assert!(tracker.get(&requests[1].request_id).is_some());
+        assert!(tracker.get(&requests[2].request_id).is_some());
+
+        // Updating a tracked request does not evict another one
+        tracker.completed(&requests[1], outcome(3));
+        assert!(tracker.get(&requests[2].request_id).is_some());
+        assert_eq!(
+            tracker.get(&requests[1].request_id).unwrap().outcome,
+            Some(outcome(3))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tracked_requests_expire() {
+        let tracker = RequestTracker::new(TrackingConfig {
+            ttl: Duration::from_millis(50),
+            max_requests: 10,
+        });
+        let request = request("user");
+        tracker.queued(&request);
+        assert!(tracker.get(&request.request_id).is_some());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(tracker.get(&request.request_id).is_none());
+
+        // The default tracker records nothing
+        let tracker = RequestTracker::default();
+        tracker.queued(&request);
+        assert!(tracker.get(&request.request_id).is_none());
+    }
+
+    #[test]
+    fn test_late_queued_does_not_undo_processing() {
+        let tracker = RequestTracker::new(TrackingConfig::default());
+        let request = request("user");
+        tracker.processing(&request);
+        tracker.queued(&request);
+
+        let tracked = tracker.get(&request.request_id).unwrap();
+        assert_eq!(tracked.state, RequestState::Processing);
+        let states: Vec<RequestState> = tracked
+            .transitions
+            .iter()
+            .map(|transition| transition.state)
+            .collect();
+        assert_eq!(states, [RequestState::Queued, RequestState::Processing]);
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use badge_forge::{
+        model::stats::UserStats,
+        service::verification::{Criterion, VerificationPolicy},
+    };
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn stats(num_recipes: u32, total_likes: u32, active_weeks: u32) -> UserStats {
+        UserStats {
+            num_recipes,
+            total_likes,
+            active_days: active_weeks,
+            active_weeks,
+        }
+    }
+
+    #[test]
+    fn test_default_policy_only_requires_recipes() {
+        let policy = VerificationPolicy::default();
+        let now = Utc::now();
+
+        let report = policy.evaluate(&stats(29, 0, 0), None, now);
+        assert!(!report.eligible);
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].criterion, Criterion::Recipes);
+        assert_eq!(report.missing[0].required, 30);
+        assert_eq!(report.missing[0].actual, 29);
+
+        let report = policy.evaluate(&stats(30, 0, 0), None, now);
+        assert!(report.eligible);
+        assert!(report.missing.is_empty());
+    }
+
+    #[test]
+    fn test_policy_reports_every_missing_criterion() {
+        let policy = VerificationPolicy {
+            min_recipes: 10,
+            min_total_likes: 100,
+            min_account_age_days: 30,
+            min_active_weeks: 4,
+        };
+        let now = Utc.with_ymd_and_hms(2025, 6, 30, 12, 0, 0).unwrap();
+        let created_at = now - Duration::days(10);
+
+        let report = policy.evaluate(&stats(12, 50, 2), Some(created_at), now);
+        assert!(!report.eligible);
+
+        let missing: Vec<_> = report.missing.iter().map(|m| m.criterion).collect();
+        assert_eq!(
+            missing,
+            vec![
+                Criterion::TotalLikes,
+                Criterion::AccountAgeDays,
+                Criterion::ActiveWeeks
+            ]
+        );
+        assert_eq!(report.missing[1].actual, 10);
+    }
+
+    #[test]
+    fn test_policy_eligible_when_all_criteria_met() {
+        let policy = VerificationPolicy {
+            min_recipes: 10,
+            min_total_likes: 100,
+            min_account_age_days: 30,
+            min_active_weeks: 4,
+        };
+        let now = Utc::now();
+
+        let report = policy.evaluate(&stats(10, 100, 4), Some(now - Duration::days(30)), now);
+        assert!(report.eligible);
+    }
+
+    #[test]
+    fn test_policy_unknown_account_age() {
+        let policy = VerificationPolicy {
+            min_recipes: 0,
+            min_total_likes: 0,
+            min_account_age_days: 1,
+            min_active_weeks: 0,
+        };
+
+        let report = policy.evaluate(&stats(0, 0, 0), None, Utc::now());
+        assert!(!report.eligible);
+        assert_eq!(report.missing[0].criterion, Criterion::AccountAgeDays);
+        assert_eq!(report.missing[0].actual, 0);
+    }
+}
@@ -2,7 +2,10 @@
 mod tests {
     use badge_forge::{
         model::recipe::Recipe,
-        utils::badge::{assign_badges, is_month_streak, is_week_streak},
+        utils::badge::{
+            assign_badges, is_month_streak, is_week_streak, needs_recipe_history,
+            top_recipe_tier_badges,
+        },
     };
     use chrono::{DateTime, TimeZone, Utc};
     use mongodb::bson::oid::ObjectId;
@@ -386,4 +389,35 @@ mod tests {
         // Should not have week streak (recipes in Week 6 are not on consecutive days)
         assert!(!is_week_streak(&recipes));
     }
+
+    #[test]
+    fn test_top_recipe_tier_badges() {
+        assert!(top_recipe_tier_badges("recipe_of_the_week", 0).is_empty());
+        assert!(top_recipe_tier_badges("recipe_of_the_week", 4).is_empty());
+        assert_eq!(
+            top_recipe_tier_badges("recipe_of_the_week", 5),
+            vec!["recipe_of_the_week_5x".to_string()]
+        );
+        assert_eq!(
+            top_recipe_tier_badges("recipe_of_the_month", 12),
+            vec![
+                "recipe_of_the_month_5x".to_string(),
+                "recipe_of_the_month_10x".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_needs_recipe_history() {
+        assert!(needs_recipe_history(&[]));
+        assert!(needs_recipe_history(&["week_streak".to_string()]));
+        assert!(needs_recipe_history(&[
+            "month_streak".to_string(),
+            "level_100".to_string()
+        ]));
+        assert!(!needs_recipe_history(&[
+            "week_streak".to_string(),
+            "month_streak".to_string()
+        ]));
+    }
 }
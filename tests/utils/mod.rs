@@ -1,3 +1,4 @@
 pub mod badge_tests;
 pub mod level_tests;
+pub mod stats_tests;
 pub mod test_utils;
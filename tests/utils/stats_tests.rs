@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use badge_forge::model::{recipe::Recipe, stats::UserStats};
+    use chrono::{TimeZone, Utc};
+    use mongodb::bson::oid::ObjectId;
+
+    fn create_recipe(likes: i32, y: i32, m: u32, d: u32, h: u32) -> Recipe {
+        Recipe {
+            _id: ObjectId::new(),
+            user_id: ObjectId::new(),
+            num_likes: likes,
+            created_at: Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        assert_eq!(UserStats::from_recipes(&[]), UserStats::default());
+    }
+
+    #[test]
+    fn test_stats_counts_distinct_days_and_weeks() {
+        let recipes = vec![
+            // Two recipes on the same day
+            create_recipe(3, 2025, 1, 6, 9),
+            create_recipe(4, 2025, 1, 6, 18),
+            // Same ISO week, different day
+            create_recipe(0, 2025, 1, 8, 12),
+            // Next ISO week
+            create_recipe(5, 2025, 1, 13, 12),
+            // ISO week 1 of 2026 starts on 2025-12-29
+            create_recipe(1, 2025, 12, 29, 12),
+        ];
+
+        let stats = UserStats::from_recipes(&recipes);
+        assert_eq!(stats.num_recipes, 5);
+        assert_eq!(stats.total_likes, 13);
+        assert_eq!(stats.active_days, 4);
+        assert_eq!(stats.active_weeks, 3);
+    }
+
+    #[test]
+    fn test_stats_ignore_negative_likes() {
+        let recipes = vec![
+            create_recipe(-2, 2025, 1, 6, 9),
+            create_recipe(2, 2025, 1, 7, 9),
+        ];
+        assert_eq!(UserStats::from_recipes(&recipes).total_likes, 2);
+    }
+}
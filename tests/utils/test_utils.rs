@@ -1,23 +1,53 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use async_trait::async_trait;
 use axum::test_helpers::TestClient;
 use badge_forge::{
     api::{route::create_router, state::AppState},
+    error::Error,
+    middleware::auth::{ApiKey, ApiKeys},
+    middleware::rate_limit::RateLimits,
+    middleware::signature::SignatureVerifier,
+    model::award::{AwardOutcome, TopRecipeAward},
+    model::category::CategoryRegistry,
     model::recipe::Recipe,
+    model::review::{BadgeReview, ReviewStatus},
+    model::stats::UserStats,
     model::user::User,
     queue::{BadgeUpdateQueue, InMemoryQueue},
-    service::db::Database,
+    service::connectivity::Connectivity,
+    service::db::{BadgeUpdate, Database, MemorySnapshot, SqliteDatabase},
+    service::heartbeat::Heartbeat,
+    service::metrics::ProcessorMetrics,
     service::notifier::Notifier,
+    service::shutdown::Shutdown,
+    service::tracking::RequestTracker,
+    service::verification::VerificationPolicy,
 };
+use chrono::{TimeZone, Utc};
 use dotenv::dotenv;
 use mongodb::bson::oid::ObjectId;
 
+pub type UserHook = Box<dyn FnOnce(&mut User) + Send>;
+
 pub struct MockDatabase {
     pub users: Mutex<HashMap<ObjectId, User>>,
     pub recipes: Mutex<HashMap<ObjectId, Vec<Recipe>>>,
+    pub reviews: Mutex<Vec<BadgeReview>>,
+    /// Number of `get_user_recipes` calls, to check when full recipe lists are loaded.
+    pub recipe_list_calls: AtomicUsize,
+    /// Runs once against the stored user right after the next `find_user` snapshot,
+    /// simulating a concurrent write landing between a read and the following update.
+    pub after_find_user: Mutex<Option<UserHook>>,
+    /// Returned by the next `find_user` call instead of the stored user.
+    pub find_user_error: Mutex<Option<Error>>,
+    /// Number of upcoming `ping` calls that fail as if the database were unreachable.
+    pub failing_pings: AtomicUsize,
+    /// Makes the next `find_user` call panic, before any lock is taken.
+    pub panic_on_find_user: AtomicBool,
 }
 
 impl MockDatabase {
@@ -31,43 +61,91 @@ impl Default for MockDatabase {
         Self {
             users: Mutex::new(HashMap::new()),
             recipes: Mutex::new(HashMap::new()),
+            reviews: Mutex::new(Vec::new()),
+            recipe_list_calls: AtomicUsize::new(0),
+            after_find_user: Mutex::new(None),
+            find_user_error: Mutex::new(None),
+            failing_pings: AtomicUsize::new(0),
+            panic_on_find_user: AtomicBool::new(false),
         }
     }
 }
 
 #[async_trait]
 impl Database for MockDatabase {
-    async fn find_user(&self, user_id: &ObjectId) -> Result<Option<User>, String> {
-        let users = self.users.lock().unwrap();
-        Ok(users.get(user_id).cloned())
+    async fn ping(&self) -> Result<(), Error> {
+        let failing = self
+            .failing_pings
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+        match failing {
+            Ok(_) => Err(Error::Transient(
+                "Database error: connection refused".to_string(),
+            )),
+            Err(_) => Ok(()),
+        }
     }
 
-    async fn update_user_badges_and_level(
+    async fn find_user(&self, user_id: &ObjectId) -> Result<Option<User>, Error> {
+        if self.panic_on_find_user.swap(false, Ordering::SeqCst) {
+            panic!("Simulated panic while loading user {}", user_id);
+        }
+        if let Some(error) = self.find_user_error.lock().unwrap().take() {
+            return Err(error);
+        }
+        let mut users = self.users.lock().unwrap();
+        let snapshot = users.get(user_id).cloned();
+        if let Some(hook) = self.after_find_user.lock().unwrap().take()
+            && let Some(user) = users.get_mut(user_id)
+        {
+            hook(user);
+        }
+        Ok(snapshot)
+    }
+
+    async fn apply_badge_update(
         &self,
         user_id: &ObjectId,
-        badges: &[String],
-        level: i32,
-        verified: bool,
-    ) -> Result<(), String> {
+        update: &BadgeUpdate,
+    ) -> Result<bool, Error> {
         let mut users = self.users.lock().unwrap();
-        if let Some(user) = users.get_mut(user_id) {
-            user.badges = badges.to_vec();
-            user.level = level;
+        let Some(user) = users.get_mut(user_id) else {
+            return Ok(false);
+        };
+        let locked = user.verification_locked.unwrap_or(false);
+        if user.level != update.expected_level || (update.verified.is_some() && locked) {
+            return Ok(false);
+        }
+
+        user.level = update.level;
+        for badge in &update.add_badges {
+            if !user.badges.contains(badge) {
+                user.badges.push(badge.clone());
+            }
+        }
+        if let Some(verified) = update.verified {
             user.verified = Some(verified);
         }
-        Ok(())
+        Ok(true)
     }
 
-    async fn get_user_recipes(&self, user_id: &ObjectId) -> Result<Vec<Recipe>, String> {
+    async fn get_user_recipes(&self, user_id: &ObjectId) -> Result<Vec<Recipe>, Error> {
+        self.recipe_list_calls.fetch_add(1, Ordering::SeqCst);
         let recipes = self.recipes.lock().unwrap();
         Ok(recipes.get(user_id).cloned().unwrap_or_default())
     }
 
+    async fn get_user_stats(&self, user_id: &ObjectId) -> Result<UserStats, Error> {
+        let recipes = self.recipes.lock().unwrap();
+        Ok(UserStats::from_recipes(
+            recipes.get(user_id).map(Vec::as_slice).unwrap_or_default(),
+        ))
+    }
+
     async fn add_badge_to_user(
         &self,
         user_id: &ObjectId,
         badge: &str,
-    ) -> Result<Option<bool>, String> {
+    ) -> Result<Option<bool>, Error> {
         let mut users = self.users.lock().unwrap();
         if let Some(user) = users.get_mut(user_id) {
             user.ensure_badges();
@@ -81,16 +159,122 @@ impl Database for MockDatabase {
             Ok(None)
         }
     }
+
+    async fn record_top_recipe_award(
+        &self,
+        user_id: &ObjectId,
+        award: &TopRecipeAward,
+    ) -> Result<Option<AwardOutcome>, Error> {
+        let mut users = self.users.lock().unwrap();
+        let Some(user) = users.get_mut(user_id) else {
+            return Ok(None);
+        };
+
+        let already_recorded = user
+            .top_recipe_awards
+            .iter()
+            .any(|a| a.badge == award.badge && a.period == award.period);
+        if !already_recorded {
+            user.top_recipe_awards.push(award.clone());
+            if !user.badges.contains(&award.badge) {
+                user.badges.push(award.badge.clone());
+            }
+        }
+
+        Ok(Some(AwardOutcome {
+            newly_awarded: !already_recorded,
+            win_count: user.top_recipe_wins(&award.badge),
+        }))
+    }
+
+    async fn set_verification(
+        &self,
+        user_id: &ObjectId,
+        verified: bool,
+        locked: bool,
+        reason: &str,
+        updated_by: &str,
+    ) -> Result<bool, Error> {
+        let mut users = self.users.lock().unwrap();
+        if let Some(user) = users.get_mut(user_id) {
+            user.verified = Some(verified);
+            user.verification_locked = Some(locked);
+            user.verification_reason = Some(reason.to_string());
+            user.verification_updated_by = Some(updated_by.to_string());
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    async fn hold_badges_for_review(&self, review: &BadgeReview) -> Result<(), Error> {
+        let mut reviews = self.reviews.lock().unwrap();
+        if let Some(pending) = reviews
+            .iter_mut()
+            .find(|r| r.user_id == review.user_id && r.status == ReviewStatus::Pending)
+        {
+            for badge in &review.badges {
+                if !pending.badges.contains(badge) {
+                    pending.badges.push(badge.clone());
+                }
+            }
+            pending.verify |= review.verify;
+            pending.signals = review.signals.clone();
+            pending.score = review.score;
+        } else {
+            reviews.push(review.clone());
+        }
+        Ok(())
+    }
+
+    async fn get_pending_badge_reviews(&self) -> Result<Vec<BadgeReview>, Error> {
+        let reviews = self.reviews.lock().unwrap();
+        Ok(reviews
+            .iter()
+            .filter(|r| r.status == ReviewStatus::Pending)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_user_badge_reviews(&self, user_id: &ObjectId) -> Result<Vec<BadgeReview>, Error> {
+        let reviews = self.reviews.lock().unwrap();
+        Ok(reviews
+            .iter()
+            .filter(|r| r.user_id == *user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn resolve_badge_review(
+        &self,
+        review_id: &ObjectId,
+        status: ReviewStatus,
+        resolved_by: &str,
+    ) -> Result<Option<BadgeReview>, Error> {
+        let mut reviews = self.reviews.lock().unwrap();
+        Ok(reviews
+            .iter_mut()
+            .find(|r| r._id == *review_id && r.status == ReviewStatus::Pending)
+            .map(|review| {
+                review.status = status;
+                review.resolved_at = Some(Utc::now());
+                review.resolved_by = Some(resolved_by.to_string());
+                review.clone()
+            }))
+    }
 }
 
 pub struct MockNotifier {
     pub notifications: Mutex<Vec<(String, String, serde_json::Value)>>,
+    /// Returned by `check` while set, as if the notifier were unreachable.
+    pub check_error: Mutex<Option<String>>,
 }
 
 impl MockNotifier {
     pub fn new() -> Self {
         Self {
             notifications: Mutex::new(Vec::new()),
+            check_error: Mutex::new(None),
         }
     }
 }
@@ -116,35 +300,185 @@ impl Notifier for MockNotifier {
             metadata,
         ));
     }
+
+    async fn check(&self) -> Result<(), String> {
+        match self.check_error.lock().unwrap().clone() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
 }
 
-pub async fn setup_test_client_with_db() -> (TestClient, Arc<MockDatabase>, Arc<MockNotifier>) {
+/// A `Database` the shared endpoint and processor tests run against.
+#[async_trait]
+pub trait TestBackend: Database + Sized + 'static {
+    fn create() -> Self;
+    /// Stores a user and their recipes directly, bypassing `Database`.
+    fn seed_user(&self, user: User, recipes: Vec<Recipe>);
+    fn seed_review(&self, review: BadgeReview);
+
+    async fn stored_user(&self, user_id: &ObjectId) -> User {
+        self.find_user(user_id).await.unwrap().unwrap()
+    }
+}
+
+#[async_trait]
+impl TestBackend for MockDatabase {
+    fn create() -> Self {
+        Self::new()
+    }
+
+    fn seed_user(&self, user: User, recipes: Vec<Recipe>) {
+        self.insert_user(user, recipes);
+    }
+
+    fn seed_review(&self, review: BadgeReview) {
+        self.reviews.lock().unwrap().push(review);
+    }
+}
+
+#[async_trait]
+impl TestBackend for SqliteDatabase {
+    fn create() -> Self {
+        Self::open_in_memory().unwrap()
+    }
+
+    fn seed_user(&self, user: User, recipes: Vec<Recipe>) {
+        self.import(&MemorySnapshot {
+            users: vec![user],
+            recipes,
+            reviews: vec![],
+        })
+        .unwrap();
+    }
+
+    fn seed_review(&self, review: BadgeReview) {
+        self.import(&MemorySnapshot {
+            reviews: vec![review],
+            ..MemorySnapshot::default()
+        })
+        .unwrap();
+    }
+}
+
+/// Generates a `#[tokio::test]` per backend for each generic `async fn name<D: TestBackend>()`
+/// of the calling module, in `mock` and `sqlite` submodules.
+#[macro_export]
+macro_rules! backend_tests {
+    ($($name:ident),* $(,)?) => {
+        mod mock {
+            $(
+                #[tokio::test]
+                async fn $name() {
+                    super::$name::<$crate::utils::test_utils::MockDatabase>().await;
+                }
+            )*
+        }
+
+        mod sqlite {
+            $(
+                #[tokio::test]
+                async fn $name() {
+                    super::$name::<badge_forge::service::db::SqliteDatabase>().await;
+                }
+            )*
+        }
+    };
+}
+
+/// The API key of test states, `API_KEY` if set.
+pub fn test_api_key() -> String {
     dotenv().ok();
+    std::env::var("API_KEY").unwrap_or_else(|_| "default_key".to_string())
+}
+
+/// Builds an application state backed by the given database and mock notifier. Tests that
+/// need a non-default configuration can override fields with struct update syntax.
+pub fn build_test_state(db: Arc<impl Database + 'static>, notifier: Arc<MockNotifier>) -> AppState {
     // Set up the badge update queue
     let (queue, _) = InMemoryQueue::new(100);
-    let queue_arc = Arc::new(queue);
-    let badge_queue = queue_arc.clone() as Arc<dyn BadgeUpdateQueue>;
+    let badge_queue = Arc::new(queue) as Arc<dyn BadgeUpdateQueue>;
+
+    AppState {
+        api_keys: ApiKeys::new(vec![ApiKey::with_all_scopes("test", test_api_key())]).unwrap(),
+        signatures: SignatureVerifier::default(),
+        rate_limits: RateLimits::default(),
+        badge_queue,
+        batch_max_size: 100,
+        db: db as Arc<dyn Database>,
+        notifier: notifier as Arc<dyn Notifier>,
+        categories: CategoryRegistry::default(),
+        verification_policy: VerificationPolicy::default(),
+        cache: None,
+        connectivity: Connectivity::default(),
+        processor_heartbeat: Heartbeat::default(),
+        processor_metrics: Arc::new(ProcessorMetrics::default()),
+        requests: RequestTracker::default(),
+        shutdown: Shutdown::new(),
+    }
+}
+
+pub async fn setup_test_client_with_db() -> (TestClient, Arc<MockDatabase>, Arc<MockNotifier>) {
+    setup_test_client_with_backend::<MockDatabase>().await
+}
+
+pub async fn setup_test_client_with_backend<D: TestBackend>()
+-> (TestClient, Arc<D>, Arc<MockNotifier>) {
+    dotenv().ok();
 
-    // Use MockDatabase
-    let mock_db = Arc::new(MockDatabase::default());
+    let db = Arc::new(D::create());
 
     // Use MockNotifier
     let mock_notifier = Arc::new(MockNotifier::new());
 
     // Create the application state
-    let state = Arc::new(AppState {
-        badge_queue,
-        db: mock_db.clone() as Arc<dyn Database>,
-        notifier: mock_notifier.clone() as Arc<dyn Notifier>,
-    });
-    (
-        TestClient::new(create_router(state)),
-        mock_db,
-        mock_notifier,
-    )
+    let state = Arc::new(build_test_state(db.clone(), mock_notifier.clone()));
+    (TestClient::new(create_router(state)), db, mock_notifier)
 }
 
 pub async fn setup_test_client() -> TestClient {
     let (client, _, _) = setup_test_client_with_db().await;
     client
 }
+
+pub fn create_test_user(badges: Vec<String>) -> User {
+    let user_oid = ObjectId::new();
+    User {
+        _id: user_oid,
+        name: Some("Test User".to_string()),
+        email: Some(format!("test_user_{}@example.com", user_oid.to_hex())),
+        level: 0,
+        badges,
+        verified: Some(false),
+        top_recipe_awards: vec![],
+        created_at: None,
+        verification_locked: None,
+        verification_reason: None,
+        verification_updated_by: None,
+    }
+}
+
+/// Creates `count` recipes with `likes` likes each, published one day apart
+/// starting on 2025-01-01.
+pub fn create_test_recipes(user_id: ObjectId, count: usize, likes: i32) -> Vec<Recipe> {
+    let base = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    (0..count)
+        .map(|day| Recipe {
+            _id: ObjectId::new(),
+            user_id,
+            num_likes: likes,
+            created_at: base + chrono::Duration::days(day as i64),
+        })
+        .collect()
+}
+
+impl MockDatabase {
+    pub fn insert_user(&self, user: User, recipes: Vec<Recipe>) {
+        self.recipes.lock().unwrap().insert(user._id, recipes);
+        self.users.lock().unwrap().insert(user._id, user);
+    }
+
+    pub fn get_user(&self, user_id: &ObjectId) -> User {
+        self.users.lock().unwrap().get(user_id).cloned().unwrap()
+    }
+}